@@ -1,18 +1,200 @@
 use snafu::prelude::*;
+use std::time::Duration;
 
 #[derive(Debug, Snafu)]
 #[snafu(visibility(pub))]
 pub enum AppError {
     #[snafu(display("HTTP error"))]
     Http { source: reqwest::Error },
-    #[snafu(display("There are an error while Json deserialization: {source}"))]
-    DeserializeJson { source: reqwest::Error },
+    #[snafu(display("Failed to build the HTTP client"))]
+    ClientBuild { source: reqwest::Error },
+    #[snafu(display("There are an error while Json deserialization of {path}: {source}"))]
+    DeserializeJson {
+        source: serde_json::Error,
+        /// Path of the endpoint which returned the body
+        path: String,
+        /// First characters of the body which failed to deserialize
+        body_preview: String,
+    },
     #[snafu(display("Rate limit exceeded: {message}"))]
     RateLimitExceeded { message: String },
+    #[snafu(display("Request timed out after {elapsed:?}"))]
+    Timeout { elapsed: Duration },
+    #[snafu(display("No book matches the search"))]
+    NoResults,
+    #[snafu(display("Expected a single book but the search matched {count}"))]
+    AmbiguousResults { count: usize },
+    #[snafu(display("Volume {id} not found"))]
+    NotFound { id: String },
+    #[snafu(display("Serialization error: {message}"))]
+    Serialize { message: String },
+    #[snafu(display("Image error: {message}"))]
+    Image { message: String },
+    #[snafu(display("Invalid query: {source}"))]
+    InvalidQuery { source: QueryError },
+    #[snafu(display("No API key was provided"))]
+    MissingApiKey,
+    #[snafu(display("Invalid API key: {reason}"))]
+    InvalidApiKey { reason: String },
+    #[snafu(display("Invalid value for the {name} header"))]
+    InvalidHeader {
+        source: reqwest::header::InvalidHeaderValue,
+        name: String,
+    },
     #[snafu(display("Google API error {code}: {message}"))]
     GoogleApi {
         code: u16,
         message: String,
         reason: Option<String>,
+        /// Canonical status of the error (e.g. "PERMISSION_DENIED")
+        status: Option<String>,
     },
 }
+
+/// Errors raised while building a query
+#[derive(Debug, Snafu)]
+#[snafu(visibility(pub))]
+pub enum QueryError {
+    #[snafu(display("The {qualifier} qualifier needs a non-empty value"))]
+    EmptyQualifier { qualifier: String },
+    #[snafu(display("The query is {len} characters long, the maximum is {max}"))]
+    QueryTooLong { len: usize, max: usize },
+}
+
+impl AppError {
+    /// Returns true when the failed request may succeed if sent again
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            AppError::Http { source } => source.is_timeout() || source.is_connect(),
+            AppError::RateLimitExceeded { .. } | AppError::Timeout { .. } => true,
+            AppError::GoogleApi { code, .. } => *code >= 500,
+            AppError::ClientBuild { .. }
+            | AppError::DeserializeJson { .. }
+            | AppError::NoResults
+            | AppError::AmbiguousResults { .. }
+            | AppError::NotFound { .. }
+            | AppError::Serialize { .. }
+            | AppError::Image { .. }
+            | AppError::InvalidQuery { .. }
+            | AppError::MissingApiKey
+            | AppError::InvalidApiKey { .. }
+            | AppError::InvalidHeader { .. } => false,
+        }
+    }
+
+    /// Canonical status reported by Google (e.g. "RESOURCE_EXHAUSTED"), if any
+    pub fn api_status(&self) -> Option<&str> {
+        match self {
+            AppError::GoogleApi { status, .. } => status.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Structured body for web backends:
+    /// `{ "error": { "type": ..., "code": ..., "message": ... } }`
+    ///
+    /// `code` is the matching HTTP status, or `null` for client-side errors.
+    #[cfg(feature = "serialize")]
+    pub fn to_json(&self) -> serde_json::Value {
+        let (error_type, code) = match self {
+            AppError::Http { .. } => ("http", None),
+            AppError::ClientBuild { .. } => ("client_build", None),
+            AppError::DeserializeJson { .. } => ("deserialize_json", None),
+            AppError::RateLimitExceeded { .. } => ("rate_limit_exceeded", Some(429)),
+            AppError::Timeout { .. } => ("timeout", None),
+            AppError::NoResults => ("no_results", None),
+            AppError::AmbiguousResults { .. } => ("ambiguous_results", None),
+            AppError::NotFound { .. } => ("not_found", Some(404)),
+            AppError::Serialize { .. } => ("serialize", None),
+            AppError::Image { .. } => ("image", None),
+            AppError::InvalidQuery { .. } => ("invalid_query", None),
+            AppError::MissingApiKey => ("missing_api_key", None),
+            AppError::InvalidApiKey { .. } => ("invalid_api_key", None),
+            AppError::InvalidHeader { .. } => ("invalid_header", None),
+            AppError::GoogleApi { code, .. } => ("google_api", Some(*code)),
+        };
+
+        serde_json::json!({
+            "error": {
+                "type": error_type,
+                "code": code,
+                "message": self.to_string(),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timeout_error() {
+        let error = AppError::Timeout {
+            elapsed: Duration::from_secs(3),
+        };
+
+        assert_eq!(error.to_string(), "Request timed out after 3s");
+        assert!(error.is_retryable());
+    }
+
+    #[test]
+    fn test_no_results_error() {
+        let error = AppError::NoResults;
+
+        assert_eq!(error.to_string(), "No book matches the search");
+        assert!(!error.is_retryable());
+    }
+
+    #[test]
+    fn test_ambiguous_results_error() {
+        let error = AppError::AmbiguousResults { count: 4 };
+
+        assert_eq!(
+            error.to_string(),
+            "Expected a single book but the search matched 4"
+        );
+        assert!(!error.is_retryable());
+    }
+
+    #[cfg(feature = "serialize")]
+    #[test]
+    fn test_google_api_error_to_json() {
+        let error = AppError::GoogleApi {
+            code: 403,
+            message: "Daily limit exceeded".to_string(),
+            reason: Some("dailyLimitExceeded".to_string()),
+            status: Some("PERMISSION_DENIED".to_string()),
+        };
+
+        assert_eq!(
+            error.to_json(),
+            serde_json::json!({
+                "error": {
+                    "type": "google_api",
+                    "code": 403,
+                    "message": "Google API error 403: Daily limit exceeded",
+                }
+            })
+        );
+    }
+
+    #[cfg(feature = "serialize")]
+    #[test]
+    fn test_not_found_error_to_json() {
+        let error = AppError::NotFound {
+            id: "zyTCAlFPjgYC".to_string(),
+        };
+
+        assert_eq!(
+            error.to_json(),
+            serde_json::json!({
+                "error": {
+                    "type": "not_found",
+                    "code": 404,
+                    "message": "Volume zyTCAlFPjgYC not found",
+                }
+            })
+        );
+    }
+}
@@ -0,0 +1,578 @@
+//! Text exports of search results (markdown, CSV, BibTeX, RIS, ...).
+
+#[cfg(feature = "serialize")]
+use crate::errors::AppError;
+use crate::models::{Book, VolumeResponse};
+#[cfg(feature = "serialize")]
+use serde::Serialize;
+
+/// Escapes the characters that would break a markdown table cell
+fn escape_markdown_cell(value: &str) -> String {
+    value.replace('|', "\\|")
+}
+
+/// Layout of `VolumeResponse::to_bibliography`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BibStyle {
+    /// One citation per line
+    Plain,
+    /// One citation per line, prefixed with `[1]`, `[2]`, ...
+    Numbered,
+}
+
+impl VolumeResponse {
+    /// Renders the books as a plain-text bibliography, one `Book::to_citation`
+    /// per line
+    pub fn to_bibliography(&self, style: BibStyle) -> String {
+        self.items
+            .iter()
+            .flatten()
+            .enumerate()
+            .map(|(i, book)| match style {
+                BibStyle::Plain => format!("{}\n", book.to_citation()),
+                BibStyle::Numbered => format!("[{}] {}\n", i + 1, book.to_citation()),
+            })
+            .collect()
+    }
+
+    /// Lists the books one per line as `id<TAB>title<TAB>isbn13` for
+    /// debugging, with an empty column for a missing ISBN-13. Tabs in titles
+    /// are replaced by spaces.
+    pub fn debug_dump(&self) -> String {
+        self.items
+            .iter()
+            .flatten()
+            .map(|book| {
+                format!(
+                    "{}\t{}\t{}\n",
+                    book.id,
+                    book.volume_info.title.replace('\t', " "),
+                    book.volume_info.identifier("ISBN_13").unwrap_or_default()
+                )
+            })
+            .collect()
+    }
+
+    /// Renders the books as a markdown table with Title, Author, Year and ISBN columns
+    pub fn to_markdown_table(&self) -> String {
+        let mut table =
+            String::from("| Title | Author | Year | ISBN |\n| --- | --- | --- | --- |\n");
+
+        for book in self.items.iter().flatten() {
+            table.push_str(&markdown_row(book));
+        }
+
+        table
+    }
+
+    /// Flattens every book for bulk exports (see `Book::to_flat`)
+    pub fn to_flat_rows(&self) -> Vec<FlatBook> {
+        self.items.iter().flatten().map(Book::to_flat).collect()
+    }
+}
+
+/// Flat view of a book for exports (CSV, ...), lists are joined with "; "
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+pub struct FlatBook {
+    pub id: String,
+    pub title: String,
+    pub subtitle: Option<String>,
+    pub authors: String,
+    pub publisher: Option<String>,
+    pub published_date: Option<String>,
+    pub isbn_10: Option<String>,
+    pub isbn_13: Option<String>,
+    pub page_count: Option<u16>,
+    pub categories: String,
+    pub thumbnail: Option<String>,
+}
+
+impl Book {
+    /// Flattens the book into a single export row
+    pub fn to_flat(&self) -> FlatBook {
+        let info = &self.volume_info;
+
+        FlatBook {
+            id: self.id.clone(),
+            title: info.title.clone(),
+            subtitle: info.subtitle.clone(),
+            authors: info.authors.as_deref().unwrap_or_default().join("; "),
+            publisher: info.publisher.clone(),
+            published_date: info.published_date.clone(),
+            isbn_10: info.identifier("ISBN_10").map(str::to_string),
+            isbn_13: info.identifier("ISBN_13").map(str::to_string),
+            page_count: info.page_count,
+            categories: info.categories.as_deref().unwrap_or_default().join("; "),
+            thumbnail: info
+                .image_links
+                .as_ref()
+                .and_then(|links| links.thumbnail.clone()),
+        }
+    }
+}
+
+/// Output formats of `GoogleBooks::export_all`
+#[cfg(feature = "serialize")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// One JSON `FlatBook` object per line
+    Ndjson,
+    /// `FlatBook` rows after a header row
+    Csv,
+}
+
+/// Writes the books to `writer` in the given format, the CSV header row is
+/// only written when `with_header` is set
+#[cfg(feature = "serialize")]
+pub(crate) fn write_books<W: std::io::Write>(
+    books: &[Book],
+    writer: &mut W,
+    format: ExportFormat,
+    with_header: bool,
+) -> Result<(), AppError> {
+    let serialize_error = |message: String| AppError::Serialize { message };
+
+    match format {
+        ExportFormat::Ndjson => {
+            for book in books {
+                serde_json::to_writer(&mut *writer, &book.to_flat())
+                    .map_err(|e| serialize_error(e.to_string()))?;
+                writer
+                    .write_all(b"\n")
+                    .map_err(|e| serialize_error(e.to_string()))?;
+            }
+        }
+        ExportFormat::Csv => {
+            let mut csv_writer = csv::WriterBuilder::new()
+                .has_headers(with_header)
+                .from_writer(&mut *writer);
+
+            for book in books {
+                csv_writer
+                    .serialize(book.to_flat())
+                    .map_err(|e| serialize_error(e.to_string()))?;
+            }
+            csv_writer
+                .flush()
+                .map_err(|e| serialize_error(e.to_string()))?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "serialize")]
+impl VolumeResponse {
+    /// Serializes the books as CSV, one `FlatBook` row per book after a header row
+    pub fn to_csv(&self) -> Result<String, AppError> {
+        let mut writer = csv::Writer::from_writer(Vec::new());
+
+        for book in self.items.iter().flatten() {
+            writer
+                .serialize(book.to_flat())
+                .map_err(|e| AppError::Serialize {
+                    message: e.to_string(),
+                })?;
+        }
+
+        let bytes = writer.into_inner().map_err(|e| AppError::Serialize {
+            message: e.to_string(),
+        })?;
+
+        Ok(String::from_utf8_lossy(&bytes).into_owned())
+    }
+}
+
+impl Book {
+    /// Renders the book as a one-line citation, leaving out the missing parts
+    /// (e.g. `Peter Kropotkin (1902). Mutual Aid: A Factor of Evolution. McClure Phillips.`)
+    pub fn to_citation(&self) -> String {
+        let info = &self.volume_info;
+        let authors = info.authors.as_deref().unwrap_or_default().join(", ");
+        let head = match info.published_year() {
+            Some(year) if authors.is_empty() => format!("({})", year),
+            Some(year) => format!("{} ({})", authors, year),
+            None => authors,
+        };
+
+        [Some(head), Some(info.full_title()), info.publisher.clone()]
+            .into_iter()
+            .flatten()
+            .filter(|part| !part.is_empty())
+            .map(|part| format!("{}.", part))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+#[cfg(feature = "bibtex")]
+impl Book {
+    /// Renders the book as a BibTeX `@book` entry, keyed by the first
+    /// author's last name and the publication year (e.g. `kropotkin1902`)
+    pub fn to_bibtex(&self) -> String {
+        let info = &self.volume_info;
+        let mut fields: Vec<(&str, String)> = vec![("title", escape_bibtex(&info.full_title()))];
+
+        if let Some(authors) = info.authors.as_ref().filter(|authors| !authors.is_empty()) {
+            let authors: Vec<String> = authors.iter().map(|author| escape_bibtex(author)).collect();
+            fields.push(("author", authors.join(" and ")));
+        }
+        if let Some(publisher) = &info.publisher {
+            fields.push(("publisher", escape_bibtex(publisher)));
+        }
+        if let Some(year) = info.published_year() {
+            fields.push(("year", year.to_string()));
+        }
+        if let Some(isbn) = info.isbn() {
+            fields.push(("isbn", isbn.to_string()));
+        }
+
+        let body: Vec<String> = fields
+            .iter()
+            .map(|(name, value)| format!("  {} = {{{}}}", name, value))
+            .collect();
+
+        format!("@book{{{},\n{}\n}}\n", bibtex_key(self), body.join(",\n"))
+    }
+}
+
+/// Citation key made of the first author's last name and the year
+#[cfg(feature = "bibtex")]
+fn bibtex_key(book: &Book) -> String {
+    let info = &book.volume_info;
+    let last_name: String = info
+        .authors
+        .as_ref()
+        .and_then(|authors| authors.first())
+        .and_then(|author| author.split_whitespace().last())
+        .unwrap_or("anonymous")
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .collect::<String>()
+        .to_lowercase();
+    let year = info
+        .published_year()
+        .map(|year| year.to_string())
+        .unwrap_or_else(|| "nd".to_string());
+
+    format!("{}{}", last_name, year)
+}
+
+/// Escapes the characters with a special meaning in BibTeX
+#[cfg(feature = "bibtex")]
+fn escape_bibtex(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+
+    for c in value.chars() {
+        match c {
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '~' => escaped.push_str("\\textasciitilde{}"),
+            '^' => escaped.push_str("\\textasciicircum{}"),
+            '\\' => escaped.push_str("\\textbackslash{}"),
+            _ => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+#[cfg(feature = "ris")]
+impl Book {
+    /// Renders the book as an RIS record (as imported by Zotero, EndNote, ...),
+    /// with one `AU` line per author and CRLF line endings
+    pub fn to_ris(&self) -> String {
+        let info = &self.volume_info;
+        let mut lines = vec![
+            "TY  - BOOK".to_string(),
+            format!("TI  - {}", info.full_title()),
+        ];
+
+        for author in info.authors.iter().flatten() {
+            lines.push(format!("AU  - {}", author));
+        }
+        if let Some(publisher) = &info.publisher {
+            lines.push(format!("PB  - {}", publisher));
+        }
+        if let Some(year) = info.published_year() {
+            lines.push(format!("PY  - {}", year));
+        }
+        if let Some(isbn) = info.isbn() {
+            lines.push(format!("SN  - {}", isbn));
+        }
+        lines.push("ER  - ".to_string());
+
+        lines.join("\r\n") + "\r\n"
+    }
+}
+
+fn markdown_row(book: &Book) -> String {
+    let info = &book.volume_info;
+    let authors = info.authors.as_deref().unwrap_or_default().join(", ");
+    let year = info
+        .published_year()
+        .map(|year| year.to_string())
+        .unwrap_or_default();
+
+    format!(
+        "| {} | {} | {} | {} |\n",
+        escape_markdown_cell(&info.title),
+        escape_markdown_cell(&authors),
+        year,
+        info.isbn().unwrap_or_default()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_markdown_table() {
+        let response: VolumeResponse = serde_json::from_str(
+            r#"{
+                "kind": "books#volumes",
+                "totalItems": 1,
+                "items": [{
+                    "id": "abc",
+                    "etag": "a",
+                    "volumeInfo": {
+                        "title": "Either | Or",
+                        "authors": ["Søren Kierkegaard"],
+                        "publishedDate": "1843-02-20",
+                        "industryIdentifiers": [
+                            { "type": "ISBN_10", "identifier": "0140445773" },
+                            { "type": "ISBN_13", "identifier": "9780140445770" }
+                        ]
+                    }
+                }]
+            }"#,
+        )
+        .unwrap();
+
+        let table = response.to_markdown_table();
+        let lines: Vec<&str> = table.lines().collect();
+
+        assert_eq!(lines[0], "| Title | Author | Year | ISBN |");
+        assert_eq!(
+            lines[2],
+            "| Either \\| Or | Søren Kierkegaard | 1843 | 9780140445770 |"
+        );
+        assert_eq!(lines.len(), 3);
+    }
+
+    #[test]
+    fn test_to_flat_rows() {
+        let response: VolumeResponse = serde_json::from_str(
+            r#"{
+                "kind": "books#volumes",
+                "totalItems": 2,
+                "items": [
+                    {
+                        "id": "first",
+                        "etag": "a",
+                        "volumeInfo": {
+                            "title": "Good Omens",
+                            "authors": ["Terry Pratchett", "Neil Gaiman"],
+                            "pageCount": 412,
+                            "industryIdentifiers": [{ "type": "ISBN_13", "identifier": "9780060853983" }]
+                        }
+                    },
+                    { "id": "second", "etag": "b", "volumeInfo": { "title": "Mort" } }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let rows = response.to_flat_rows();
+
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].id, "first");
+        assert_eq!(rows[0].authors, "Terry Pratchett; Neil Gaiman");
+        assert_eq!(rows[0].isbn_13.as_deref(), Some("9780060853983"));
+        assert_eq!(rows[0].page_count, Some(412));
+        assert_eq!(rows[1].authors, "");
+        assert_eq!(rows[1].isbn_13, None);
+    }
+
+    #[test]
+    fn test_debug_dump() {
+        let response: VolumeResponse = serde_json::from_str(
+            r#"{
+                "kind": "books#volumes",
+                "totalItems": 2,
+                "items": [
+                    {
+                        "id": "abc",
+                        "etag": "a",
+                        "volumeInfo": {
+                            "title": "Either/Or",
+                            "industryIdentifiers": [{ "type": "ISBN_13", "identifier": "9780140445770" }]
+                        }
+                    },
+                    { "id": "def", "etag": "b", "volumeInfo": { "title": "Fear and Trembling" } }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let dump = response.debug_dump();
+        let lines: Vec<&str> = dump.lines().collect();
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0], "abc\tEither/Or\t9780140445770");
+        assert_eq!(
+            lines[1].split('\t').collect::<Vec<_>>(),
+            vec!["def", "Fear and Trembling", ""]
+        );
+    }
+
+    fn bibliography_response() -> VolumeResponse {
+        serde_json::from_str(
+            r#"{
+                "kind": "books#volumes",
+                "totalItems": 2,
+                "items": [
+                    {
+                        "id": "first",
+                        "etag": "a",
+                        "volumeInfo": {
+                            "title": "Mutual Aid",
+                            "subtitle": "A Factor of Evolution",
+                            "authors": ["Peter Kropotkin"],
+                            "publisher": "McClure Phillips",
+                            "publishedDate": "1902"
+                        }
+                    },
+                    { "id": "second", "etag": "b", "volumeInfo": { "title": "Fields, Factories and Workshops" } }
+                ]
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_to_bibliography_plain() {
+        let bibliography = bibliography_response().to_bibliography(BibStyle::Plain);
+        let lines: Vec<&str> = bibliography.lines().collect();
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(
+            lines[0],
+            "Peter Kropotkin (1902). Mutual Aid: A Factor of Evolution. McClure Phillips."
+        );
+        assert_eq!(lines[1], "Fields, Factories and Workshops.");
+    }
+
+    #[test]
+    fn test_to_bibliography_numbered() {
+        let bibliography = bibliography_response().to_bibliography(BibStyle::Numbered);
+        let lines: Vec<&str> = bibliography.lines().collect();
+
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("[1] Peter Kropotkin"));
+        assert_eq!(lines[1], "[2] Fields, Factories and Workshops.");
+    }
+
+    #[cfg(feature = "serialize")]
+    #[test]
+    fn test_to_csv() {
+        let response: VolumeResponse = serde_json::from_str(
+            r#"{
+                "kind": "books#volumes",
+                "totalItems": 2,
+                "items": [
+                    {
+                        "id": "first",
+                        "etag": "a",
+                        "volumeInfo": { "title": "Mutual Aid", "authors": ["Peter Kropotkin"] }
+                    },
+                    {
+                        "id": "second",
+                        "etag": "b",
+                        "volumeInfo": { "title": "Fields, Factories and Workshops", "pageCount": 256 }
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let csv = response.to_csv().unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(
+            lines[0],
+            "id,title,subtitle,authors,publisher,published_date,isbn_10,isbn_13,page_count,categories,thumbnail"
+        );
+        assert_eq!(lines.len(), 3);
+        assert!(lines[2].starts_with("second,\"Fields, Factories and Workshops\""));
+    }
+
+    #[cfg(feature = "bibtex")]
+    #[test]
+    fn test_to_bibtex() {
+        let book: Book = serde_json::from_str(
+            r#"{
+                "id": "abc",
+                "etag": "a",
+                "volumeInfo": {
+                    "title": "Mutual Aid",
+                    "subtitle": "A Factor of Evolution",
+                    "authors": ["Peter Kropotkin"],
+                    "publisher": "McClure Phillips & Co",
+                    "publishedDate": "1902",
+                    "industryIdentifiers": [
+                        { "type": "ISBN_13", "identifier": "9780486449135" }
+                    ]
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let entry = book.to_bibtex();
+
+        assert!(entry.starts_with("@book{kropotkin1902,\n"));
+        assert!(entry.contains("  title = {Mutual Aid: A Factor of Evolution}"));
+        assert!(entry.contains("  author = {Peter Kropotkin}"));
+        assert!(entry.contains("  publisher = {McClure Phillips \\& Co}"));
+        assert!(entry.contains("  year = {1902}"));
+        assert!(entry.contains("  isbn = {9780486449135}"));
+        assert!(entry.ends_with("}\n"));
+    }
+
+    #[cfg(feature = "ris")]
+    #[test]
+    fn test_to_ris() {
+        let book: Book = serde_json::from_str(
+            r#"{
+                "id": "abc",
+                "etag": "a",
+                "volumeInfo": {
+                    "title": "Good Omens",
+                    "authors": ["Terry Pratchett", "Neil Gaiman"],
+                    "publisher": "Gollancz",
+                    "publishedDate": "1990-05-01",
+                    "industryIdentifiers": [
+                        { "type": "ISBN_10", "identifier": "0575048530" }
+                    ]
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let record = book.to_ris();
+        let lines: Vec<&str> = record.lines().collect();
+
+        assert_eq!(lines.first(), Some(&"TY  - BOOK"));
+        assert!(lines.contains(&"TI  - Good Omens"));
+        assert!(lines.contains(&"AU  - Terry Pratchett"));
+        assert!(lines.contains(&"AU  - Neil Gaiman"));
+        assert!(lines.contains(&"PB  - Gollancz"));
+        assert!(lines.contains(&"PY  - 1990"));
+        assert!(lines.contains(&"SN  - 0575048530"));
+        assert_eq!(lines.last(), Some(&"ER  - "));
+    }
+}
@@ -0,0 +1,53 @@
+//! ISBN helpers.
+
+/// Strips separators (hyphens, spaces, ...) from an ISBN, keeping its digits
+/// and an uppercased `X` check digit
+pub fn normalize(isbn: &str) -> String {
+    isbn.chars()
+        .filter(|c| c.is_ascii_digit() || c.eq_ignore_ascii_case(&'x'))
+        .map(|c| c.to_ascii_uppercase())
+        .collect()
+}
+
+/// Converts an ISBN-10 to its 978-prefixed ISBN-13, recomputing the check
+/// digit. Returns `None` when the input doesn't have ten ISBN characters.
+pub fn isbn10_to_isbn13(isbn10: &str) -> Option<String> {
+    let isbn10 = normalize(isbn10);
+    if isbn10.len() != 10 || !isbn10[..9].chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let body = format!("978{}", &isbn10[..9]);
+    let sum: u32 = body
+        .chars()
+        .filter_map(|c| c.to_digit(10))
+        .enumerate()
+        .map(|(i, digit)| if i % 2 == 0 { digit } else { digit * 3 })
+        .sum();
+
+    Some(format!("{}{}", body, (10 - sum % 10) % 10))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize("978-2-348-05469-3"), "9782348054693");
+        assert_eq!(normalize("0 19 853453 x"), "019853453X");
+    }
+
+    #[test]
+    fn test_isbn10_to_isbn13() {
+        assert_eq!(
+            isbn10_to_isbn13("2-348-05469-3").as_deref(),
+            Some("9782348054693")
+        );
+        assert_eq!(
+            isbn10_to_isbn13("019853453X").as_deref(),
+            Some("9780198534532")
+        );
+        assert_eq!(isbn10_to_isbn13("12345"), None);
+    }
+}
@@ -1,7 +1,13 @@
-use serde::Deserialize;
+use crate::{errors::AppError, isbn, queries::VolumeQuery};
+#[cfg(feature = "serialize")]
+use serde::Serialize;
+use serde::{Deserialize, Deserializer};
+#[cfg(feature = "capture-extra")]
+use std::collections::HashMap;
+use std::{cmp::Ordering, collections::BTreeMap, fmt::Display, str::FromStr};
 
 /// Main response from Google Books API
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 pub struct VolumeResponse {
     pub kind: String,
     #[serde(rename(deserialize = "totalItems"))]
@@ -9,8 +15,343 @@ pub struct VolumeResponse {
     pub items: Option<Vec<Book>>,
 }
 
+impl VolumeResponse {
+    /// Collapses editions of the same work into their best edition, keeping the
+    /// order in which works first appear
+    pub fn distinct_works(self) -> VolumeResponse {
+        let mut works: Vec<(String, Vec<Book>)> = Vec::new();
+
+        for book in self.items.unwrap_or_default() {
+            let key = book.work_key();
+            match works.iter_mut().find(|(work, _)| *work == key) {
+                Some((_, editions)) => editions.push(book),
+                None => works.push((key, vec![book])),
+            }
+        }
+
+        let items: Vec<Book> = works
+            .iter()
+            .filter_map(|(_, editions)| best_edition(editions).cloned())
+            .collect();
+
+        VolumeResponse {
+            kind: self.kind,
+            total_items: items.len() as i32,
+            items: Some(items),
+        }
+    }
+
+    /// Appends the books of `other`, dropping books whose work (see
+    /// `Book::work_key`) is already present. `total_items` becomes the number
+    /// of merged books.
+    pub fn merge(self, other: VolumeResponse) -> VolumeResponse {
+        let mut keys: Vec<String> = Vec::new();
+        let mut items: Vec<Book> = Vec::new();
+
+        for book in self
+            .items
+            .into_iter()
+            .flatten()
+            .chain(other.items.into_iter().flatten())
+        {
+            let key = book.work_key();
+            if !keys.contains(&key) {
+                keys.push(key);
+                items.push(book);
+            }
+        }
+
+        VolumeResponse {
+            kind: self.kind,
+            total_items: items.len() as i32,
+            items: Some(items),
+        }
+    }
+
+    /// Compact fingerprint of the ordered book ids and etags, to cheaply detect
+    /// whether results changed between two polls.
+    ///
+    /// Uses 64-bit FNV-1a, so fingerprints are stable across runs and
+    /// compiler versions.
+    pub fn fingerprint(&self) -> String {
+        let mut hash: u64 = 0xcbf29ce484222325;
+
+        for book in self.items.iter().flatten() {
+            for bytes in [book.id.as_bytes(), book.etag.as_bytes()] {
+                for byte in bytes.iter().chain(&[0]) {
+                    hash ^= u64::from(*byte);
+                    hash = hash.wrapping_mul(0x100000001b3);
+                }
+            }
+        }
+
+        format!("{:016x}", hash)
+    }
+
+    /// Returns the only book of the response, failing with `AppError::NoResults`
+    /// or `AppError::AmbiguousResults` otherwise
+    pub fn into_single(self) -> Result<Book, AppError> {
+        let mut items = self.items.unwrap_or_default();
+
+        match items.len() {
+            0 => Err(AppError::NoResults),
+            1 => Ok(items.remove(0)),
+            count => Err(AppError::AmbiguousResults { count }),
+        }
+    }
+
+    /// Returns the items whose print type is "BOOK", dropping magazines and
+    /// other content types
+    pub fn books_only(&self) -> Vec<&Book> {
+        self.items
+            .iter()
+            .flatten()
+            .filter(|book| book.volume_info.print_type == "BOOK")
+            .collect()
+    }
+
+    /// Returns the books sorted client-side by the given key.
+    /// Books missing the key always come last, whatever the direction.
+    pub fn sorted_by(&self, key: SortKey, descending: bool) -> Vec<&Book> {
+        let mut books: Vec<&Book> = self.items.iter().flatten().collect();
+
+        books.sort_by(|a, b| {
+            let (a, b) = (&a.volume_info, &b.volume_info);
+            match key {
+                SortKey::Title => compare_known(
+                    Some(a.title.to_lowercase()),
+                    Some(b.title.to_lowercase()),
+                    descending,
+                ),
+                SortKey::Year => compare_known(a.published_year(), b.published_year(), descending),
+                SortKey::PageCount => compare_known(a.page_count, b.page_count, descending),
+                SortKey::Rating => compare_known(a.average_rating, b.average_rating, descending),
+            }
+        });
+
+        books
+    }
+
+    /// Iterates over the `(id, title)` of each book, skipping books with an
+    /// empty title
+    pub fn id_title_pairs(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.items
+            .iter()
+            .flatten()
+            .filter(|book| !book.volume_info.title.is_empty())
+            .map(|book| (book.id.as_str(), book.volume_info.title.as_str()))
+    }
+
+    /// Keeps only the first `n` books, `total_items` still reports the
+    /// original total
+    pub fn take(mut self, n: usize) -> VolumeResponse {
+        if let Some(items) = self.items.as_mut() {
+            items.truncate(n);
+        }
+
+        self
+    }
+
+    /// Returns true when both responses list the same books in the same
+    /// order, comparing their IDs
+    pub fn same_page_as(&self, other: &VolumeResponse) -> bool {
+        self.items.iter().flatten().map(|book| &book.id).eq(other
+            .items
+            .iter()
+            .flatten()
+            .map(|book| &book.id))
+    }
+
+    /// Sorts the books by ID in place, giving a deterministic order for
+    /// snapshot tests
+    pub fn canonical_sort(&mut self) {
+        if let Some(items) = self.items.as_mut() {
+            items.sort_by(|a, b| a.id.cmp(&b.id));
+        }
+    }
+
+    /// Number of pages of `page_size` books needed for `total_items` (the
+    /// last page may be partial). Zero for a non-positive `page_size`.
+    pub fn total_pages(&self, page_size: i32) -> i32 {
+        if page_size <= 0 {
+            return 0;
+        }
+
+        let total = self.total_items.max(0);
+
+        total / page_size + i32::from(total % page_size != 0)
+    }
+
+    /// Number of pages left after the page starting at `start_index`, based
+    /// on the reported `total_items` (the last page may be partial).
+    /// Never negative, and zero for a non-positive `page_size`.
+    pub fn remaining_pages(&self, start_index: i32, page_size: i32) -> i32 {
+        if page_size <= 0 {
+            return 0;
+        }
+
+        // i64 so that indexes or page sizes close to i32::MAX can't overflow
+        let page_size = i64::from(page_size);
+        let remaining_items =
+            i64::from(self.total_items) - i64::from(start_index.max(0)) - page_size;
+        if remaining_items <= 0 {
+            return 0;
+        }
+
+        // Never more than `total_items`, so it fits back in an i32
+        ((remaining_items + page_size - 1) / page_size) as i32
+    }
+
+    /// Wraps the books of the page starting at `start_index` in a paginated
+    /// envelope for API backends. `page` is 1-based.
+    pub fn to_page_envelope(&self, start_index: i32, page_size: i32) -> PageEnvelope<Book> {
+        let page = if page_size > 0 {
+            (start_index.max(0) / page_size).saturating_add(1)
+        } else {
+            1
+        };
+
+        PageEnvelope {
+            items: self.items.clone().unwrap_or_default(),
+            total: self.total_items,
+            page,
+            per_page: page_size,
+            has_next: self.remaining_pages(start_index, page_size) > 0,
+        }
+    }
+
+    /// Returns the books whose publisher is in `allowed` (case-insensitive).
+    /// Books without a publisher are left out.
+    pub fn only_publishers(&self, allowed: &[&str]) -> Vec<&Book> {
+        self.items
+            .iter()
+            .flatten()
+            .filter(|book| {
+                book.volume_info
+                    .publisher
+                    .as_ref()
+                    .is_some_and(|publisher| {
+                        allowed
+                            .iter()
+                            .any(|allowed| allowed.to_lowercase() == publisher.to_lowercase())
+                    })
+            })
+            .collect()
+    }
+
+    /// Returns the books which look like real books, dropping placeholder
+    /// entries: books with an empty or whitespace-only title, and books
+    /// with neither an author nor an industry identifier
+    pub fn filter_junk(&self) -> Vec<&Book> {
+        self.items
+            .iter()
+            .flatten()
+            .filter(|book| {
+                let info = &book.volume_info;
+                let has_authors = info.authors.as_ref().is_some_and(|a| !a.is_empty());
+                let has_identifiers = info
+                    .industry_identifiers
+                    .as_ref()
+                    .is_some_and(|ids| !ids.is_empty());
+
+                !info.title.trim().is_empty() && (has_authors || has_identifiers)
+            })
+            .collect()
+    }
+
+    /// Returns the books which have at least one cover thumbnail
+    pub fn with_cover_only(&self) -> Vec<&Book> {
+        self.items
+            .iter()
+            .flatten()
+            .filter(|book| book.has_cover())
+            .collect()
+    }
+
+    /// Groups the books by publication decade (e.g. 1990 for 1994), skipping
+    /// books without a parseable publication year
+    pub fn by_decade(&self) -> BTreeMap<i32, Vec<&Book>> {
+        let mut decades: BTreeMap<i32, Vec<&Book>> = BTreeMap::new();
+
+        for book in self.items.iter().flatten() {
+            if let Some(year) = book.volume_info.published_year() {
+                decades.entry(year / 10 * 10).or_default().push(book);
+            }
+        }
+
+        decades
+    }
+
+    /// Counts the books per language, books without one are counted as "und"
+    ///
+    /// Useful to detect results in another language when `langRestrict` is
+    /// ignored by the server
+    pub fn language_breakdown(&self) -> BTreeMap<String, usize> {
+        let mut languages = BTreeMap::new();
+
+        for book in self.items.iter().flatten() {
+            let language = book.volume_info.language.as_deref().unwrap_or("und");
+            *languages.entry(language.to_string()).or_insert(0) += 1;
+        }
+
+        languages
+    }
+
+    /// Counts the books per category, most frequent first and
+    /// alphabetically on ties, e.g. as tag cloud input
+    pub fn category_frequencies(&self) -> Vec<(String, usize)> {
+        frequencies(
+            self.items
+                .iter()
+                .flatten()
+                .flat_map(|book| book.volume_info.categories.iter().flatten()),
+        )
+    }
+
+    /// Counts the books per author, most frequent first and alphabetically
+    /// on ties
+    pub fn author_frequencies(&self) -> Vec<(String, usize)> {
+        frequencies(
+            self.items
+                .iter()
+                .flatten()
+                .flat_map(|book| book.volume_info.authors.iter().flatten()),
+        )
+    }
+
+    /// Counts the books per publisher, most frequent first and
+    /// alphabetically on ties
+    pub fn publisher_frequencies(&self) -> Vec<(String, usize)> {
+        frequencies(
+            self.items
+                .iter()
+                .flatten()
+                .filter_map(|book| book.volume_info.publisher.as_ref()),
+        )
+    }
+
+    /// Author, category and publisher frequencies, e.g. for a facet sidebar
+    pub fn facets(&self) -> Facets {
+        Facets {
+            authors: self.author_frequencies(),
+            categories: self.category_frequencies(),
+            publishers: self.publisher_frequencies(),
+        }
+    }
+
+    /// Returns the books available for download in the given format
+    pub fn with_format(&self, format: DownloadFormat) -> Vec<&Book> {
+        self.items
+            .iter()
+            .flatten()
+            .filter(|book| book.has_format(format))
+            .collect()
+    }
+}
+
 /// Represents a book with its basic metadata
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
 pub struct Book {
     pub id: String,
     pub etag: String,
@@ -19,10 +360,254 @@ pub struct Book {
     pub self_link: Option<String>,
     #[serde(rename(deserialize = "volumeInfo"))]
     pub volume_info: VolumeInfo,
+    #[serde(rename(deserialize = "accessInfo"))]
+    pub access_info: Option<AccessInfo>,
+}
+
+impl Book {
+    /// Returns the names of the metadata fields which differ between two books
+    pub fn diff(&self, other: &Book) -> Vec<&'static str> {
+        let (a, b) = (&self.volume_info, &other.volume_info);
+        let fields = [
+            ("title", a.title != b.title),
+            ("subtitle", a.subtitle != b.subtitle),
+            ("authors", a.authors != b.authors),
+            ("publisher", a.publisher != b.publisher),
+            ("published_date", a.published_date != b.published_date),
+            ("description", a.description != b.description),
+            (
+                "industry_identifiers",
+                a.industry_identifiers != b.industry_identifiers,
+            ),
+            ("page_count", a.page_count != b.page_count),
+            ("print_type", a.print_type != b.print_type),
+            ("categories", a.categories != b.categories),
+        ];
+
+        fields
+            .into_iter()
+            .filter(|(_, differs)| *differs)
+            .map(|(name, _)| name)
+            .collect()
+    }
+
+    /// Removes tracking query parameters (see `TRACKING_PARAMS`) from the
+    /// preview, info, canonical and cover links
+    pub fn clean_links(&mut self) {
+        let info = &mut self.volume_info;
+        strip_tracking_params(&mut info.preview_link);
+        strip_tracking_params(&mut info.info_link);
+        strip_tracking_params(&mut info.canonical_volume_link);
+
+        if let Some(links) = info.image_links.as_mut() {
+            for link in [
+                &mut links.small_thumbnail,
+                &mut links.thumbnail,
+                &mut links.small,
+                &mut links.medium,
+                &mut links.large,
+                &mut links.extra_large,
+            ] {
+                strip_tracking_params(link);
+            }
+
+            #[cfg(feature = "capture-extra")]
+            for value in links.extra_sizes.values_mut() {
+                let mut link = Some(std::mem::take(value));
+                strip_tracking_params(&mut link);
+                *value = link.unwrap_or_default();
+            }
+        }
+    }
+
+    /// Key shared by all editions of the same work (title and first author)
+    pub fn work_key(&self) -> String {
+        let info = &self.volume_info;
+        let author = info
+            .authors
+            .as_ref()
+            .and_then(|authors| authors.first())
+            .map(|author| author.trim().to_lowercase())
+            .unwrap_or_default();
+
+        format!("{}|{}", normalize_title(&info.title), author)
+    }
+
+    /// Metadata quality score from 0 to 100, adding 20 points for each of:
+    /// a description, an ISBN-13, a cover, a page count and an average rating
+    pub fn quality_score(&self) -> u32 {
+        let info = &self.volume_info;
+        let criteria = [
+            info.description.is_some(),
+            info.identifier("ISBN_13").is_some(),
+            self.has_cover(),
+            info.page_count.is_some(),
+            info.average_rating.is_some(),
+        ];
+
+        criteria.iter().filter(|met| **met).count() as u32 * 20
+    }
+
+    /// Query for similar books: the first author and the first category,
+    /// whichever are available, or the title when the book has neither
+    pub fn more_like_this_query(&self) -> VolumeQuery {
+        let info = &self.volume_info;
+        let author = info.authors.iter().flatten().next();
+        let category = info.categories.iter().flatten().next();
+
+        match (author, category) {
+            (Some(author), Some(category)) => {
+                VolumeQuery::author(author.clone()).and_subject(category.clone())
+            }
+            (Some(author), None) => VolumeQuery::author(author.clone()),
+            (None, Some(category)) => VolumeQuery::subject(category.clone()),
+            (None, None) => VolumeQuery::title(info.title.clone()),
+        }
+    }
+
+    /// Returns false when the book lists both an ISBN-10 and an ISBN-13 and
+    /// the ISBN-13 isn't the 978-prefixed form of the ISBN-10
+    pub fn identifiers_consistent(&self) -> bool {
+        let info = &self.volume_info;
+
+        match (info.identifier("ISBN_10"), info.identifier("ISBN_13")) {
+            (Some(isbn10), Some(isbn13)) => {
+                isbn::isbn10_to_isbn13(isbn10) == Some(isbn::normalize(isbn13))
+            }
+            _ => true,
+        }
+    }
+
+    /// Returns true when the book has a non-empty cover thumbnail
+    pub fn has_cover(&self) -> bool {
+        self.volume_info.image_links.as_ref().is_some_and(|links| {
+            [&links.small_thumbnail, &links.thumbnail]
+                .iter()
+                .any(|link| link.as_deref().is_some_and(|link| !link.is_empty()))
+        })
+    }
+
+    /// Returns true when the book advertises the given download format as available
+    pub fn has_format(&self, format: DownloadFormat) -> bool {
+        self.access_info
+            .as_ref()
+            .and_then(|access| match format {
+                DownloadFormat::Epub => access.epub.as_ref(),
+                DownloadFormat::Pdf => access.pdf.as_ref(),
+            })
+            .is_some_and(|availability| availability.is_available)
+    }
+}
+
+/// Counts the values, most frequent first and alphabetically on ties
+fn frequencies<'a>(values: impl Iterator<Item = &'a String>) -> Vec<(String, usize)> {
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+
+    for value in values {
+        *counts.entry(value.as_str()).or_insert(0) += 1;
+    }
+
+    let mut frequencies: Vec<(String, usize)> = counts
+        .into_iter()
+        .map(|(value, count)| (value.to_string(), count))
+        .collect();
+    // Stable sort, so ties keep the alphabetical order of the BTreeMap
+    frequencies.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+
+    frequencies
+}
+
+/// Frequencies of the authors, categories and publishers of a response,
+/// each sorted by descending frequency then alphabetically
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+pub struct Facets {
+    pub authors: Vec<(String, usize)>,
+    pub categories: Vec<(String, usize)>,
+    pub publishers: Vec<(String, usize)>,
+}
+
+/// A page of results with its pagination metadata
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+pub struct PageEnvelope<T> {
+    pub items: Vec<T>,
+    /// Total number of results reported by Google
+    pub total: i32,
+    /// 1-based page number
+    pub page: i32,
+    pub per_page: i32,
+    pub has_next: bool,
+}
+
+/// Query parameters added by Google Books for tracking purposes
+pub const TRACKING_PARAMS: [&str; 6] = [
+    "source",
+    "utm_source",
+    "utm_medium",
+    "utm_campaign",
+    "utm_term",
+    "utm_content",
+];
+
+/// Removes the tracking query parameters from a link, leaving unparseable links untouched
+fn strip_tracking_params(link: &mut Option<String>) {
+    let Some(mut url) = link
+        .as_deref()
+        .and_then(|link| reqwest::Url::parse(link).ok())
+    else {
+        return;
+    };
+
+    let kept: Vec<(String, String)> = url
+        .query_pairs()
+        .filter(|(key, _)| !TRACKING_PARAMS.contains(&key.as_ref()))
+        .map(|(key, value)| (key.into_owned(), value.into_owned()))
+        .collect();
+
+    if kept.is_empty() {
+        url.set_query(None);
+    } else {
+        url.query_pairs_mut().clear().extend_pairs(kept);
+    }
+
+    *link = Some(url.to_string());
+}
+
+/// Leading articles ignored when comparing titles
+const LEADING_ARTICLES: [&str; 6] = ["the", "a", "an", "le", "la", "les"];
+
+/// Normalizes a title for matching: lowercases it, strips punctuation,
+/// collapses whitespace and drops a leading article
+pub fn normalize_title(title: &str) -> String {
+    let cleaned: String = title
+        .to_lowercase()
+        .chars()
+        .filter(|c| c.is_alphanumeric() || c.is_whitespace())
+        .collect();
+    let mut words: Vec<&str> = cleaned.split_whitespace().collect();
+
+    if words.len() > 1 && LEADING_ARTICLES.contains(&words[0]) {
+        words.remove(0);
+    }
+
+    words.join(" ")
+}
+
+/// Picks the edition with the best `Book::quality_score` among books
+/// describing the same work. On ties the first edition wins.
+pub fn best_edition(editions: &[Book]) -> Option<&Book> {
+    editions
+        .iter()
+        .fold(None, |best: Option<&Book>, book| match best {
+            Some(current) if current.quality_score() >= book.quality_score() => Some(current),
+            _ => Some(book),
+        })
 }
 
 /// Detailed information about a book
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
 pub struct VolumeInfo {
     pub title: String,
     pub subtitle: Option<String>,
@@ -33,7 +618,11 @@ pub struct VolumeInfo {
     pub description: Option<String>,
     #[serde(rename(deserialize = "industryIdentifiers"))]
     pub industry_identifiers: Option<Vec<IndustryIdentifiers>>,
-    #[serde(rename(deserialize = "pageCount"))]
+    #[serde(
+        rename(deserialize = "pageCount"),
+        default,
+        deserialize_with = "lenient_number"
+    )]
     pub page_count: Option<u16>,
     #[serde(
         rename(deserialize = "printType"),
@@ -41,26 +630,234 @@ pub struct VolumeInfo {
     )]
     pub print_type: String,
     pub categories: Option<Vec<String>>,
+    #[serde(
+        rename(deserialize = "averageRating"),
+        default,
+        deserialize_with = "lenient_number"
+    )]
+    pub average_rating: Option<f32>,
+    #[serde(
+        rename(deserialize = "ratingsCount"),
+        default,
+        deserialize_with = "lenient_number"
+    )]
+    pub ratings_count: Option<u32>,
     #[serde(rename(deserialize = "imageLinks"))]
     pub image_links: Option<ImageLink>,
+    #[serde(rename(deserialize = "previewLink"))]
+    pub preview_link: Option<String>,
+    #[serde(rename(deserialize = "infoLink"))]
+    pub info_link: Option<String>,
+    #[serde(rename(deserialize = "canonicalVolumeLink"))]
+    pub canonical_volume_link: Option<String>,
+    /// ISO 639-1 code of the book language (e.g. "en")
+    pub language: Option<String>,
 }
 
 impl VolumeInfo {
     pub fn default_print_type() -> String {
         "".to_string()
     }
+
+    /// Year parsed from the `publishedDate` field (e.g. "2004-05-01" gives 2004)
+    pub fn published_year(&self) -> Option<i32> {
+        self.published_date
+            .as_deref()
+            .and_then(|date| date.get(..4))
+            .and_then(|year| year.parse().ok())
+    }
+
+    /// Title followed by the subtitle, if any (e.g. "Mutual Aid: A Factor of Evolution")
+    pub fn full_title(&self) -> String {
+        match &self.subtitle {
+            Some(subtitle) => format!("{}: {}", self.title, subtitle),
+            None => self.title.clone(),
+        }
+    }
+
+    /// Returns the identifier of the given type (e.g. "ISBN_13")
+    pub fn identifier(&self, identifier_type: &str) -> Option<&str> {
+        self.industry_identifiers
+            .iter()
+            .flatten()
+            .find(|id| id.identifier_type == identifier_type)
+            .map(|id| id.identifier.as_str())
+    }
+
+    /// Returns the ISBN-13, falling back to the ISBN-10
+    pub fn isbn(&self) -> Option<&str> {
+        self.identifier("ISBN_13")
+            .or_else(|| self.identifier("ISBN_10"))
+    }
+}
+
+/// Deserializes an optional number which Google sometimes encodes as a
+/// string (e.g. `"250"`), an empty string giving `None`
+fn lenient_number<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de> + FromStr,
+    T::Err: Display,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum NumberOrString<T> {
+        Number(T),
+        String(String),
+    }
+
+    match Option::<NumberOrString<T>>::deserialize(deserializer)? {
+        Some(NumberOrString::Number(number)) => Ok(Some(number)),
+        Some(NumberOrString::String(value)) if value.trim().is_empty() => Ok(None),
+        Some(NumberOrString::String(value)) => value
+            .trim()
+            .parse()
+            .map(Some)
+            .map_err(serde::de::Error::custom),
+        None => Ok(None),
+    }
 }
 
 /// Links to cover images
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
 pub struct ImageLink {
     #[serde(rename(deserialize = "smallThumbnail"))]
     pub small_thumbnail: Option<String>,
     pub thumbnail: Option<String>,
+    pub small: Option<String>,
+    pub medium: Option<String>,
+    pub large: Option<String>,
+    #[serde(rename(deserialize = "extraLarge"))]
+    pub extra_large: Option<String>,
+    /// Sizes not modeled above, keyed by their Google name
+    #[cfg(feature = "capture-extra")]
+    #[serde(flatten)]
+    pub extra_sizes: HashMap<String, String>,
+}
+
+impl ImageLink {
+    /// Returns the link of a size by its Google name (e.g. "extraLarge"),
+    /// including the sizes captured in `extra_sizes` with the
+    /// `capture-extra` feature
+    pub fn size(&self, name: &str) -> Option<&str> {
+        let modeled = match name {
+            "smallThumbnail" => &self.small_thumbnail,
+            "thumbnail" => &self.thumbnail,
+            "small" => &self.small,
+            "medium" => &self.medium,
+            "large" => &self.large,
+            "extraLarge" => &self.extra_large,
+            #[cfg(feature = "capture-extra")]
+            _ => return self.extra_sizes.get(name).map(String::as_str),
+            #[cfg(not(feature = "capture-extra"))]
+            _ => return None,
+        };
+
+        modeled.as_deref()
+    }
+
+    /// Returns the available image whose width is the closest to `target_px`
+    ///
+    /// Google doesn't report image sizes, the assumed widths are:
+    /// `smallThumbnail` 80px, `thumbnail` 128px, `small` 300px,
+    /// `medium` 575px, `large` 800px and `extraLarge` 1280px.
+    /// On a tie the smaller image is returned.
+    pub fn closest_to_width(&self, target_px: u32) -> Option<&str> {
+        [
+            (80_u32, &self.small_thumbnail),
+            (128, &self.thumbnail),
+            (300, &self.small),
+            (575, &self.medium),
+            (800, &self.large),
+            (1280, &self.extra_large),
+        ]
+        .into_iter()
+        .filter_map(|(width, link)| {
+            link.as_deref()
+                .filter(|link| !link.is_empty())
+                .map(|link| (width, link))
+        })
+        .min_by_key(|(width, _)| width.abs_diff(target_px))
+        .map(|(_, link)| link)
+    }
+}
+
+/// Access and availability information of a book
+#[derive(Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+pub struct AccessInfo {
+    pub country: Option<String>,
+    pub embeddable: Option<bool>,
+    #[serde(rename(deserialize = "publicDomain"))]
+    pub public_domain: Option<bool>,
+    pub viewability: Option<Viewability>,
+    pub epub: Option<FormatAvailability>,
+    pub pdf: Option<FormatAvailability>,
+    #[serde(rename(deserialize = "webReaderLink"))]
+    pub web_reader_link: Option<String>,
+}
+
+/// How much of a book can be read on Google Books
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+pub enum Viewability {
+    #[serde(rename = "NO_PAGES")]
+    NoPages,
+    #[serde(rename = "PARTIAL")]
+    Partial,
+    #[serde(rename = "ALL_PAGES")]
+    AllPages,
+    #[serde(other)]
+    Unknown,
+}
+
+/// Availability of a downloadable format (EPUB or PDF)
+#[derive(Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+pub struct FormatAvailability {
+    #[serde(rename(deserialize = "isAvailable"))]
+    pub is_available: bool,
+    #[serde(rename(deserialize = "acsTokenLink"))]
+    pub acs_token_link: Option<String>,
+}
+
+/// Download formats offered by Google Books
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DownloadFormat {
+    Epub,
+    Pdf,
+}
+
+/// Keys available to sort books client-side
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortKey {
+    Title,
+    Year,
+    PageCount,
+    Rating,
+}
+
+/// Compares two optional values, placing missing ones last
+fn compare_known<T: PartialOrd>(a: Option<T>, b: Option<T>, descending: bool) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => {
+            let ordering = a.partial_cmp(&b).unwrap_or(Ordering::Equal);
+            if descending {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        }
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
 }
 
 /// Book standard identifiers (ISBN-10, ISBN-13, etc.)
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
 pub struct IndustryIdentifiers {
     pub identifier: String,
     #[serde(rename(deserialize = "type"))]
@@ -90,3 +887,1014 @@ pub struct GoogleApiErrorItem {
     pub domain: String,
     pub reason: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(json: &str) -> VolumeResponse {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_into_single_without_books() {
+        let response = response(r#"{ "kind": "books#volumes", "totalItems": 0 }"#);
+        assert!(matches!(response.into_single(), Err(AppError::NoResults)));
+    }
+
+    #[test]
+    fn test_into_single_with_one_book() {
+        let response = response(
+            r#"{
+                "kind": "books#volumes",
+                "totalItems": 1,
+                "items": [{ "id": "only", "etag": "a", "volumeInfo": { "title": "Walden" } }]
+            }"#,
+        );
+        assert_eq!(response.into_single().unwrap().id, "only");
+    }
+
+    #[test]
+    fn test_into_single_with_many_books() {
+        let response = response(
+            r#"{
+                "kind": "books#volumes",
+                "totalItems": 2,
+                "items": [
+                    { "id": "first", "etag": "a", "volumeInfo": { "title": "Walden" } },
+                    { "id": "second", "etag": "b", "volumeInfo": { "title": "Walden" } }
+                ]
+            }"#,
+        );
+        assert!(matches!(
+            response.into_single(),
+            Err(AppError::AmbiguousResults { count: 2 })
+        ));
+    }
+
+    #[test]
+    fn test_normalize_title() {
+        assert_eq!(normalize_title("The Great Gatsby"), "great gatsby");
+        assert_eq!(
+            normalize_title("The Great Gatsby"),
+            normalize_title("  great   Gatsby!")
+        );
+        assert_eq!(normalize_title("The"), "the");
+    }
+
+    #[test]
+    fn test_distinct_works() {
+        let response = response(
+            r#"{
+                "kind": "books#volumes",
+                "totalItems": 3,
+                "items": [
+                    {
+                        "id": "paperback",
+                        "etag": "a",
+                        "volumeInfo": { "title": "Mutual Aid", "authors": ["Peter Kropotkin"] }
+                    },
+                    {
+                        "id": "other",
+                        "etag": "b",
+                        "volumeInfo": { "title": "The Conquest of Bread", "authors": ["Peter Kropotkin"] }
+                    },
+                    {
+                        "id": "hardcover",
+                        "etag": "c",
+                        "volumeInfo": {
+                            "title": "mutual aid ",
+                            "authors": ["Peter Kropotkin"],
+                            "pageCount": 362
+                        }
+                    }
+                ]
+            }"#,
+        );
+
+        let distinct = response.distinct_works();
+        let ids: Vec<&str> = distinct
+            .items
+            .iter()
+            .flatten()
+            .map(|book| book.id.as_str())
+            .collect();
+
+        assert_eq!(ids, vec!["hardcover", "other"]);
+        assert_eq!(distinct.total_items, 2);
+    }
+
+    #[test]
+    fn test_books_only() {
+        let response = response(
+            r#"{
+                "kind": "books#volumes",
+                "totalItems": 3,
+                "items": [
+                    { "id": "book", "etag": "a", "volumeInfo": { "title": "A", "printType": "BOOK" } },
+                    { "id": "magazine", "etag": "b", "volumeInfo": { "title": "B", "printType": "MAGAZINE" } },
+                    { "id": "unknown", "etag": "c", "volumeInfo": { "title": "C" } }
+                ]
+            }"#,
+        );
+
+        let ids: Vec<&str> = response
+            .books_only()
+            .iter()
+            .map(|book| book.id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["book"]);
+    }
+
+    #[test]
+    fn test_clean_links() {
+        let mut book: Book = serde_json::from_str(
+            r#"{
+                "id": "zyTCAlFPjgYC",
+                "etag": "a",
+                "volumeInfo": {
+                    "title": "The Google Story",
+                    "previewLink": "http://books.google.com/books?id=zyTCAlFPjgYC&printsec=frontcover&source=gbs_api",
+                    "infoLink": "http://books.google.com/books?id=zyTCAlFPjgYC&source=gbs_api",
+                    "imageLinks": {
+                        "smallThumbnail": "http://books.google.com/books/content?id=zyTCAlFPjgYC&zoom=5&source=gbs_api",
+                        "thumbnail": "http://books.google.com/books/content?id=zyTCAlFPjgYC&zoom=1&source=gbs_api&utm_source=feed",
+                        "small": "http://books.google.com/books/content?id=zyTCAlFPjgYC&zoom=2&source=gbs_api",
+                        "medium": "http://books.google.com/books/content?id=zyTCAlFPjgYC&zoom=3&source=gbs_api",
+                        "large": "http://books.google.com/books/content?id=zyTCAlFPjgYC&zoom=4&source=gbs_api",
+                        "extraLarge": "http://books.google.com/books/content?id=zyTCAlFPjgYC&zoom=6&source=gbs_api",
+                        "ultraWide": "http://books.google.com/books/content?id=zyTCAlFPjgYC&zoom=7&source=gbs_api"
+                    }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        book.clean_links();
+        let info = &book.volume_info;
+        let links = info.image_links.as_ref().unwrap();
+
+        for (link, zoom) in [
+            (&links.small_thumbnail, 5),
+            (&links.small, 2),
+            (&links.medium, 3),
+            (&links.large, 4),
+            (&links.extra_large, 6),
+        ] {
+            assert_eq!(
+                link.as_deref(),
+                Some(
+                    format!("http://books.google.com/books/content?id=zyTCAlFPjgYC&zoom={zoom}")
+                        .as_str()
+                )
+            );
+        }
+        #[cfg(feature = "capture-extra")]
+        assert_eq!(
+            links.size("ultraWide"),
+            Some("http://books.google.com/books/content?id=zyTCAlFPjgYC&zoom=7")
+        );
+
+        assert_eq!(
+            info.preview_link.as_deref(),
+            Some("http://books.google.com/books?id=zyTCAlFPjgYC&printsec=frontcover")
+        );
+        assert_eq!(
+            info.info_link.as_deref(),
+            Some("http://books.google.com/books?id=zyTCAlFPjgYC")
+        );
+        assert_eq!(
+            info.image_links.as_ref().unwrap().thumbnail.as_deref(),
+            Some("http://books.google.com/books/content?id=zyTCAlFPjgYC&zoom=1")
+        );
+    }
+
+    #[test]
+    fn test_diff() {
+        let book: Book = serde_json::from_str(
+            r#"{
+                "id": "a",
+                "etag": "a",
+                "volumeInfo": {
+                    "title": "Walden",
+                    "authors": ["Henry David Thoreau"],
+                    "publisher": "Ticknor and Fields",
+                    "pageCount": 357
+                }
+            }"#,
+        )
+        .unwrap();
+        let other: Book = serde_json::from_str(
+            r#"{
+                "id": "b",
+                "etag": "b",
+                "volumeInfo": {
+                    "title": "Walden",
+                    "authors": ["Henry David Thoreau"],
+                    "publisher": "Penguin Classics",
+                    "pageCount": 384
+                }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(book.diff(&other), vec!["publisher", "page_count"]);
+        assert!(book.diff(&book).is_empty());
+    }
+
+    fn sortable_response() -> VolumeResponse {
+        response(
+            r#"{
+                "kind": "books#volumes",
+                "totalItems": 3,
+                "items": [
+                    {
+                        "id": "middle",
+                        "etag": "a",
+                        "volumeInfo": {
+                            "title": "Middlemarch",
+                            "publishedDate": "1871",
+                            "pageCount": 880,
+                            "averageRating": 4.0
+                        }
+                    },
+                    {
+                        "id": "unknown",
+                        "etag": "b",
+                        "volumeInfo": { "title": "Unknown" }
+                    },
+                    {
+                        "id": "emma",
+                        "etag": "c",
+                        "volumeInfo": {
+                            "title": "emma",
+                            "publishedDate": "1815-12-23",
+                            "pageCount": 474,
+                            "averageRating": 4.5
+                        }
+                    }
+                ]
+            }"#,
+        )
+    }
+
+    fn sorted_ids(response: &VolumeResponse, key: SortKey, descending: bool) -> Vec<&str> {
+        response
+            .sorted_by(key, descending)
+            .iter()
+            .map(|book| book.id.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_sorted_by_title() {
+        let response = sortable_response();
+        assert_eq!(
+            sorted_ids(&response, SortKey::Title, false),
+            vec!["emma", "middle", "unknown"]
+        );
+        assert_eq!(
+            sorted_ids(&response, SortKey::Title, true),
+            vec!["unknown", "middle", "emma"]
+        );
+    }
+
+    #[test]
+    fn test_sorted_by_year() {
+        let response = sortable_response();
+        assert_eq!(
+            sorted_ids(&response, SortKey::Year, false),
+            vec!["emma", "middle", "unknown"]
+        );
+        assert_eq!(
+            sorted_ids(&response, SortKey::Year, true),
+            vec!["middle", "emma", "unknown"]
+        );
+    }
+
+    #[test]
+    fn test_sorted_by_page_count() {
+        let response = sortable_response();
+        assert_eq!(
+            sorted_ids(&response, SortKey::PageCount, false),
+            vec!["emma", "middle", "unknown"]
+        );
+        assert_eq!(
+            sorted_ids(&response, SortKey::PageCount, true),
+            vec!["middle", "emma", "unknown"]
+        );
+    }
+
+    #[test]
+    fn test_sorted_by_rating() {
+        let response = sortable_response();
+        assert_eq!(
+            sorted_ids(&response, SortKey::Rating, false),
+            vec!["middle", "emma", "unknown"]
+        );
+        assert_eq!(
+            sorted_ids(&response, SortKey::Rating, true),
+            vec!["emma", "middle", "unknown"]
+        );
+    }
+
+    fn book(volume_info: &str) -> Book {
+        serde_json::from_str(&format!(
+            r#"{{ "id": "abc", "etag": "a", "volumeInfo": {volume_info} }}"#
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_numeric_fields_as_numbers() {
+        let book = book(
+            r#"{ "title": "Walden", "pageCount": 250, "averageRating": 4.5, "ratingsCount": 12 }"#,
+        );
+        let info = book.volume_info;
+
+        assert_eq!(info.page_count, Some(250));
+        assert_eq!(info.average_rating, Some(4.5));
+        assert_eq!(info.ratings_count, Some(12));
+    }
+
+    #[test]
+    fn test_numeric_fields_as_strings() {
+        let book = book(
+            r#"{ "title": "Walden", "pageCount": "250", "averageRating": "4.5", "ratingsCount": "" }"#,
+        );
+        let info = book.volume_info;
+
+        assert_eq!(info.page_count, Some(250));
+        assert_eq!(info.average_rating, Some(4.5));
+        assert_eq!(info.ratings_count, None);
+    }
+
+    #[test]
+    fn test_numeric_fields_reject_garbage() {
+        let result: Result<Book, _> = serde_json::from_str(
+            r#"{ "id": "abc", "etag": "a", "volumeInfo": { "title": "Walden", "pageCount": "many" } }"#,
+        );
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_more_like_this_with_author_and_category() {
+        let book = book(
+            r#"{ "title": "The Dispossessed", "authors": ["Ursula K. Le Guin"], "categories": ["Fiction"] }"#,
+        );
+        assert_eq!(
+            book.more_like_this_query().q,
+            "inauthor:Ursula K. Le Guin subject:Fiction"
+        );
+    }
+
+    #[test]
+    fn test_more_like_this_with_author_only() {
+        let book = book(r#"{ "title": "The Dispossessed", "authors": ["Ursula K. Le Guin"] }"#);
+        assert_eq!(book.more_like_this_query().q, "inauthor:Ursula K. Le Guin");
+    }
+
+    #[test]
+    fn test_more_like_this_with_title_only() {
+        let book = book(r#"{ "title": "The Dispossessed" }"#);
+        assert_eq!(book.more_like_this_query().q, "intitle:The Dispossessed");
+    }
+
+    fn book_with_isbns(isbn10: &str, isbn13: &str) -> Book {
+        serde_json::from_str(&format!(
+            r#"{{
+                "id": "abc",
+                "etag": "a",
+                "volumeInfo": {{
+                    "title": "La Conquête du pain",
+                    "industryIdentifiers": [
+                        {{ "type": "ISBN_10", "identifier": "{isbn10}" }},
+                        {{ "type": "ISBN_13", "identifier": "{isbn13}" }}
+                    ]
+                }}
+            }}"#
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_identifiers_consistent() {
+        assert!(book_with_isbns("2348054693", "9782348054693").identifiers_consistent());
+    }
+
+    #[test]
+    fn test_identifiers_inconsistent() {
+        assert!(!book_with_isbns("2348054693", "9782070360024").identifiers_consistent());
+    }
+
+    #[test]
+    fn test_id_title_pairs() {
+        let response = response(
+            r#"{
+                "kind": "books#volumes",
+                "totalItems": 3,
+                "items": [
+                    { "id": "a", "etag": "a", "volumeInfo": { "title": "Beloved" } },
+                    { "id": "b", "etag": "b", "volumeInfo": { "title": "" } },
+                    { "id": "c", "etag": "c", "volumeInfo": { "title": "Jazz" } }
+                ]
+            }"#,
+        );
+
+        let pairs: Vec<(&str, &str)> = response.id_title_pairs().collect();
+
+        assert_eq!(pairs, vec![("a", "Beloved"), ("c", "Jazz")]);
+    }
+
+    #[test]
+    fn test_take() {
+        let response = response(
+            r#"{
+                "kind": "books#volumes",
+                "totalItems": 120,
+                "items": [
+                    { "id": "a", "etag": "a", "volumeInfo": { "title": "A" } },
+                    { "id": "b", "etag": "b", "volumeInfo": { "title": "B" } },
+                    { "id": "c", "etag": "c", "volumeInfo": { "title": "C" } }
+                ]
+            }"#,
+        );
+
+        let taken = response.take(2);
+
+        assert_eq!(taken.items.as_ref().map(Vec::len), Some(2));
+        assert_eq!(taken.items.unwrap()[1].id, "b");
+        assert_eq!(taken.total_items, 120);
+    }
+
+    fn page(ids: &[&str]) -> VolumeResponse {
+        let items: Vec<String> = ids
+            .iter()
+            .map(|id| {
+                format!(r#"{{ "id": "{id}", "etag": "e", "volumeInfo": {{ "title": "T" }} }}"#)
+            })
+            .collect();
+
+        response(&format!(
+            r#"{{ "kind": "books#volumes", "totalItems": {}, "items": [{}] }}"#,
+            ids.len(),
+            items.join(",")
+        ))
+    }
+
+    #[test]
+    fn test_same_page_as_identical() {
+        assert!(page(&["a", "b", "c"]).same_page_as(&page(&["a", "b", "c"])));
+    }
+
+    #[test]
+    fn test_same_page_as_reordered() {
+        assert!(!page(&["a", "b", "c"]).same_page_as(&page(&["c", "a", "b"])));
+    }
+
+    #[test]
+    fn test_same_page_as_different_books() {
+        assert!(!page(&["a", "b"]).same_page_as(&page(&["a", "d"])));
+        assert!(!page(&["a", "b"]).same_page_as(&page(&["a", "b", "c"])));
+    }
+
+    #[test]
+    fn test_canonical_sort() {
+        let mut response = response(
+            r#"{
+                "kind": "books#volumes",
+                "totalItems": 3,
+                "items": [
+                    { "id": "mK3", "etag": "a", "volumeInfo": { "title": "A" } },
+                    { "id": "aZ9", "etag": "b", "volumeInfo": { "title": "B" } },
+                    { "id": "f01", "etag": "c", "volumeInfo": { "title": "C" } }
+                ]
+            }"#,
+        );
+
+        response.canonical_sort();
+        let ids: Vec<&str> = response
+            .items
+            .iter()
+            .flatten()
+            .map(|book| book.id.as_str())
+            .collect();
+
+        assert_eq!(ids, vec!["aZ9", "f01", "mK3"]);
+    }
+
+    #[test]
+    fn test_total_pages() {
+        let exact = response(r#"{ "kind": "books#volumes", "totalItems": 40 }"#);
+        let partial = response(r#"{ "kind": "books#volumes", "totalItems": 95 }"#);
+
+        assert_eq!(exact.total_pages(10), 4);
+        assert_eq!(partial.total_pages(10), 10);
+        assert_eq!(partial.total_pages(0), 0);
+        assert_eq!(partial.total_pages(-5), 0);
+        assert_eq!(partial.total_pages(i32::MAX), 1);
+        assert_eq!(exact.total_pages(i32::MAX), 1);
+    }
+
+    #[test]
+    fn test_remaining_pages() {
+        let response = response(r#"{ "kind": "books#volumes", "totalItems": 95 }"#);
+
+        assert_eq!(response.remaining_pages(0, 10), 9);
+        assert_eq!(response.remaining_pages(50, 10), 4);
+        assert_eq!(response.remaining_pages(90, 10), 0);
+        assert_eq!(response.remaining_pages(200, 10), 0);
+        assert_eq!(response.remaining_pages(0, 0), 0);
+    }
+
+    #[test]
+    fn test_remaining_pages_near_i32_max() {
+        let small = response(r#"{ "kind": "books#volumes", "totalItems": 95 }"#);
+        assert_eq!(small.remaining_pages(i32::MAX, 100), 0);
+        assert_eq!(small.remaining_pages(0, i32::MAX), 0);
+        assert_eq!(small.remaining_pages(i32::MIN, 10), 9);
+
+        let huge = response(r#"{ "kind": "books#volumes", "totalItems": 2147483647 }"#);
+        assert_eq!(huge.remaining_pages(0, 1), i32::MAX - 1);
+        assert_eq!(huge.remaining_pages(0, i32::MAX), 0);
+        assert_eq!(huge.remaining_pages(i32::MAX, i32::MAX), 0);
+    }
+
+    #[test]
+    fn test_to_page_envelope() {
+        let response = response(
+            r#"{
+                "kind": "books#volumes",
+                "totalItems": 25,
+                "items": [
+                    { "id": "a", "etag": "a", "volumeInfo": { "title": "A" } },
+                    { "id": "b", "etag": "b", "volumeInfo": { "title": "B" } }
+                ]
+            }"#,
+        );
+
+        let envelope = response.to_page_envelope(10, 10);
+        assert_eq!(envelope.items.len(), 2);
+        assert_eq!(envelope.total, 25);
+        assert_eq!(envelope.page, 2);
+        assert_eq!(envelope.per_page, 10);
+        assert!(envelope.has_next);
+
+        let last = response.to_page_envelope(20, 10);
+        assert_eq!(last.page, 3);
+        assert!(!last.has_next);
+
+        let far = response.to_page_envelope(i32::MAX, 1);
+        assert_eq!(far.page, i32::MAX);
+        assert!(!far.has_next);
+    }
+
+    #[cfg(feature = "serialize")]
+    #[test]
+    fn test_page_envelope_serializes() {
+        let response = response(
+            r#"{
+                "kind": "books#volumes",
+                "totalItems": 1,
+                "items": [{ "id": "a", "etag": "a", "volumeInfo": { "title": "Walden" } }]
+            }"#,
+        );
+
+        let json: serde_json::Value = serde_json::from_str(
+            &serde_json::to_string(&response.to_page_envelope(0, 10)).unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(json["items"][0]["id"], "a");
+        assert_eq!(json["items"][0]["volume_info"]["title"], "Walden");
+        assert_eq!(json["total"], 1);
+        assert_eq!(json["page"], 1);
+        assert_eq!(json["has_next"], false);
+    }
+
+    #[test]
+    fn test_merge() {
+        let english = response(
+            r#"{
+                "kind": "books#volumes",
+                "totalItems": 2,
+                "items": [
+                    { "id": "en-1", "etag": "a", "volumeInfo": { "title": "Germinal", "authors": ["Émile Zola"] } },
+                    { "id": "en-2", "etag": "b", "volumeInfo": { "title": "Nana", "authors": ["Émile Zola"] } }
+                ]
+            }"#,
+        );
+        let french = response(
+            r#"{
+                "kind": "books#volumes",
+                "totalItems": 2,
+                "items": [
+                    { "id": "fr-1", "etag": "c", "volumeInfo": { "title": "Germinal", "authors": ["Émile Zola"] } },
+                    { "id": "fr-2", "etag": "d", "volumeInfo": { "title": "L'Assommoir", "authors": ["Émile Zola"] } }
+                ]
+            }"#,
+        );
+
+        let merged = english.merge(french);
+        let ids: Vec<&str> = merged
+            .items
+            .iter()
+            .flatten()
+            .map(|book| book.id.as_str())
+            .collect();
+
+        assert_eq!(ids, vec!["en-1", "en-2", "fr-2"]);
+        assert_eq!(merged.total_items, 3);
+    }
+
+    #[test]
+    fn test_only_publishers() {
+        let response = response(
+            r#"{
+                "kind": "books#volumes",
+                "totalItems": 4,
+                "items": [
+                    { "id": "lux", "etag": "a", "volumeInfo": { "title": "A", "publisher": "Lux Éditeur" } },
+                    { "id": "other", "etag": "b", "volumeInfo": { "title": "B", "publisher": "Gallimard" } },
+                    { "id": "none", "etag": "c", "volumeInfo": { "title": "C" } },
+                    { "id": "libertalia", "etag": "d", "volumeInfo": { "title": "D", "publisher": "LIBERTALIA" } }
+                ]
+            }"#,
+        );
+
+        let ids: Vec<&str> = response
+            .only_publishers(&["lux éditeur", "Libertalia"])
+            .iter()
+            .map(|book| book.id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["lux", "libertalia"]);
+    }
+
+    #[test]
+    fn test_with_cover_only() {
+        let response = response(
+            r#"{
+                "kind": "books#volumes",
+                "totalItems": 4,
+                "items": [
+                    {
+                        "id": "thumbnail",
+                        "etag": "a",
+                        "volumeInfo": { "title": "A", "imageLinks": { "thumbnail": "http://covers/a" } }
+                    },
+                    {
+                        "id": "small",
+                        "etag": "b",
+                        "volumeInfo": { "title": "B", "imageLinks": { "smallThumbnail": "http://covers/b" } }
+                    },
+                    {
+                        "id": "empty",
+                        "etag": "c",
+                        "volumeInfo": { "title": "C", "imageLinks": { "thumbnail": "" } }
+                    },
+                    { "id": "none", "etag": "d", "volumeInfo": { "title": "D" } }
+                ]
+            }"#,
+        );
+
+        let ids: Vec<&str> = response
+            .with_cover_only()
+            .iter()
+            .map(|book| book.id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["thumbnail", "small"]);
+    }
+
+    #[test]
+    fn test_quality_score() {
+        let full: Book = serde_json::from_str(
+            r#"{
+                "id": "full",
+                "etag": "a",
+                "volumeInfo": {
+                    "title": "Walden",
+                    "description": "Life in the woods",
+                    "industryIdentifiers": [{ "type": "ISBN_13", "identifier": "9780691096124" }],
+                    "imageLinks": { "thumbnail": "http://covers/walden" },
+                    "pageCount": 352,
+                    "averageRating": 4.0
+                }
+            }"#,
+        )
+        .unwrap();
+        let bare: Book = serde_json::from_str(
+            r#"{ "id": "bare", "etag": "b", "volumeInfo": { "title": "Walden" } }"#,
+        )
+        .unwrap();
+
+        assert_eq!(full.quality_score(), 100);
+        assert_eq!(bare.quality_score(), 0);
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        let json = r#"{
+            "kind": "books#volumes",
+            "totalItems": 2,
+            "items": [
+                { "id": "a", "etag": "1", "volumeInfo": { "title": "A" } },
+                { "id": "b", "etag": "2", "volumeInfo": { "title": "B" } }
+            ]
+        }"#;
+        let reordered = response(
+            r#"{
+                "kind": "books#volumes",
+                "totalItems": 2,
+                "items": [
+                    { "id": "b", "etag": "2", "volumeInfo": { "title": "B" } },
+                    { "id": "a", "etag": "1", "volumeInfo": { "title": "A" } }
+                ]
+            }"#,
+        );
+        let changed = response(
+            r#"{
+                "kind": "books#volumes",
+                "totalItems": 2,
+                "items": [
+                    { "id": "a", "etag": "1", "volumeInfo": { "title": "A" } },
+                    { "id": "b", "etag": "3", "volumeInfo": { "title": "B" } }
+                ]
+            }"#,
+        );
+
+        assert_eq!(response(json).fingerprint(), response(json).fingerprint());
+        assert_ne!(response(json).fingerprint(), reordered.fingerprint());
+        assert_ne!(response(json).fingerprint(), changed.fingerprint());
+    }
+
+    #[test]
+    fn test_by_decade() {
+        let response = response(
+            r#"{
+                "kind": "books#volumes",
+                "totalItems": 4,
+                "items": [
+                    { "id": "neuromancer", "etag": "a", "volumeInfo": { "title": "A", "publishedDate": "1984-07-01" } },
+                    { "id": "undated", "etag": "b", "volumeInfo": { "title": "B" } },
+                    { "id": "snow-crash", "etag": "c", "volumeInfo": { "title": "C", "publishedDate": "1992" } },
+                    { "id": "count-zero", "etag": "d", "volumeInfo": { "title": "D", "publishedDate": "1986" } }
+                ]
+            }"#,
+        );
+
+        let decades: Vec<(i32, Vec<&str>)> = response
+            .by_decade()
+            .into_iter()
+            .map(|(decade, books)| (decade, books.iter().map(|book| book.id.as_str()).collect()))
+            .collect();
+
+        assert_eq!(
+            decades,
+            vec![
+                (1980, vec!["neuromancer", "count-zero"]),
+                (1990, vec!["snow-crash"])
+            ]
+        );
+    }
+
+    fn image_links() -> ImageLink {
+        serde_json::from_str(
+            r#"{
+                "smallThumbnail": "https://books.google.com/small-thumbnail",
+                "thumbnail": "https://books.google.com/thumbnail",
+                "medium": "https://books.google.com/medium"
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_size() {
+        assert_eq!(
+            image_links().size("medium"),
+            Some("https://books.google.com/medium")
+        );
+        assert_eq!(image_links().size("large"), None);
+    }
+
+    #[cfg(feature = "capture-extra")]
+    #[test]
+    fn test_size_captures_extra_sizes() {
+        let links: ImageLink = serde_json::from_str(
+            r#"{
+                "thumbnail": "https://books.google.com/thumbnail",
+                "ultraWide": "https://books.google.com/ultra-wide"
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            links.size("ultraWide"),
+            Some("https://books.google.com/ultra-wide")
+        );
+        assert_eq!(
+            links.size("thumbnail"),
+            Some("https://books.google.com/thumbnail")
+        );
+        assert!(!links.extra_sizes.contains_key("thumbnail"));
+    }
+
+    #[test]
+    fn test_closest_to_width_small_target() {
+        assert_eq!(
+            image_links().closest_to_width(100),
+            Some("https://books.google.com/small-thumbnail")
+        );
+        assert_eq!(
+            image_links().closest_to_width(200),
+            Some("https://books.google.com/thumbnail")
+        );
+    }
+
+    #[test]
+    fn test_closest_to_width_large_target() {
+        assert_eq!(
+            image_links().closest_to_width(1200),
+            Some("https://books.google.com/medium")
+        );
+    }
+
+    #[test]
+    fn test_language_breakdown() {
+        let response = response(
+            r#"{
+                "kind": "books#volumes",
+                "totalItems": 4,
+                "items": [
+                    { "id": "a", "etag": "a", "volumeInfo": { "title": "A", "language": "en" } },
+                    { "id": "b", "etag": "b", "volumeInfo": { "title": "B", "language": "fr" } },
+                    { "id": "c", "etag": "c", "volumeInfo": { "title": "C" } },
+                    { "id": "d", "etag": "d", "volumeInfo": { "title": "D", "language": "en" } }
+                ]
+            }"#,
+        );
+
+        let breakdown = response.language_breakdown();
+
+        assert_eq!(breakdown.len(), 3);
+        assert_eq!(breakdown["en"], 2);
+        assert_eq!(breakdown["fr"], 1);
+        assert_eq!(breakdown["und"], 1);
+    }
+
+    #[test]
+    fn test_category_frequencies() {
+        let response = response(
+            r#"{
+                "kind": "books#volumes",
+                "totalItems": 4,
+                "items": [
+                    { "id": "a", "etag": "a", "volumeInfo": { "title": "A", "categories": ["Fiction", "History"] } },
+                    { "id": "b", "etag": "b", "volumeInfo": { "title": "B", "categories": ["History", "Science"] } },
+                    { "id": "c", "etag": "c", "volumeInfo": { "title": "C", "categories": ["Art", "Fiction", "History"] } },
+                    { "id": "d", "etag": "d", "volumeInfo": { "title": "D" } }
+                ]
+            }"#,
+        );
+
+        assert_eq!(
+            response.category_frequencies(),
+            vec![
+                ("History".to_string(), 3),
+                ("Fiction".to_string(), 2),
+                ("Art".to_string(), 1),
+                ("Science".to_string(), 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_facets() {
+        let response = response(
+            r#"{
+                "kind": "books#volumes",
+                "totalItems": 3,
+                "items": [
+                    {
+                        "id": "a",
+                        "etag": "a",
+                        "volumeInfo": {
+                            "title": "Good Omens",
+                            "authors": ["Terry Pratchett", "Neil Gaiman"],
+                            "publisher": "Gollancz",
+                            "categories": ["Fiction"]
+                        }
+                    },
+                    {
+                        "id": "b",
+                        "etag": "b",
+                        "volumeInfo": {
+                            "title": "Mort",
+                            "authors": ["Terry Pratchett"],
+                            "publisher": "Gollancz",
+                            "categories": ["Fiction", "Fantasy"]
+                        }
+                    },
+                    { "id": "c", "etag": "c", "volumeInfo": { "title": "Coraline", "authors": ["Neil Gaiman"] } }
+                ]
+            }"#,
+        );
+
+        let facets = response.facets();
+
+        assert_eq!(
+            facets.authors,
+            vec![
+                ("Neil Gaiman".to_string(), 2),
+                ("Terry Pratchett".to_string(), 2)
+            ]
+        );
+        assert_eq!(
+            facets.categories,
+            vec![("Fiction".to_string(), 2), ("Fantasy".to_string(), 1)]
+        );
+        assert_eq!(facets.publishers, vec![("Gollancz".to_string(), 2)]);
+    }
+
+    #[test]
+    fn test_filter_junk() {
+        let response = response(
+            r#"{
+                "kind": "books#volumes",
+                "totalItems": 5,
+                "items": [
+                    { "id": "authored", "etag": "a", "volumeInfo": { "title": "Ubik", "authors": ["Philip K. Dick"] } },
+                    { "id": "blank", "etag": "b", "volumeInfo": { "title": "  ", "authors": ["Philip K. Dick"] } },
+                    {
+                        "id": "identified",
+                        "etag": "c",
+                        "volumeInfo": {
+                            "title": "Valis",
+                            "industryIdentifiers": [{ "type": "ISBN_13", "identifier": "9780547572413" }]
+                        }
+                    },
+                    { "id": "bare", "etag": "d", "volumeInfo": { "title": "Untitled", "authors": [] } },
+                    { "id": "other", "etag": "e", "volumeInfo": { "title": "Ubik", "authors": ["Philip K. Dick"] } }
+                ]
+            }"#,
+        );
+
+        let ids: Vec<&str> = response
+            .filter_junk()
+            .iter()
+            .map(|book| book.id.as_str())
+            .collect();
+
+        assert_eq!(ids, vec!["authored", "identified", "other"]);
+    }
+
+    #[test]
+    fn test_with_format() {
+        let response = response(
+            r#"{
+                "kind": "books#volumes",
+                "totalItems": 3,
+                "items": [
+                    {
+                        "id": "epub",
+                        "etag": "a",
+                        "volumeInfo": { "title": "EPUB only" },
+                        "accessInfo": {
+                            "epub": { "isAvailable": true },
+                            "pdf": { "isAvailable": false }
+                        }
+                    },
+                    {
+                        "id": "pdf",
+                        "etag": "b",
+                        "volumeInfo": { "title": "PDF only" },
+                        "accessInfo": {
+                            "epub": { "isAvailable": false },
+                            "pdf": { "isAvailable": true }
+                        }
+                    },
+                    {
+                        "id": "none",
+                        "etag": "c",
+                        "volumeInfo": { "title": "No download" }
+                    }
+                ]
+            }"#,
+        );
+
+        let epub: Vec<&str> = response
+            .with_format(DownloadFormat::Epub)
+            .iter()
+            .map(|book| book.id.as_str())
+            .collect();
+        let pdf: Vec<&str> = response
+            .with_format(DownloadFormat::Pdf)
+            .iter()
+            .map(|book| book.id.as_str())
+            .collect();
+
+        assert_eq!(epub, vec!["epub"]);
+        assert_eq!(pdf, vec!["pdf"]);
+    }
+}
@@ -17,7 +17,29 @@
 //!     .max_results(10)
 //!     .projection(Projection::Lite);
 
+use crate::{
+    errors::{EmptyQualifierSnafu, QueryError, QueryTooLongSnafu},
+    isbn,
+};
 use serde::Deserialize;
+use snafu::prelude::*;
+
+/// Number of results returned by the API when `maxResults` is not set
+pub const DEFAULT_MAX_RESULTS: i32 = 10;
+
+/// Maximum length of `q`, longer queries risk exceeding Google's URL limits
+/// (HTTP 414)
+pub const MAX_QUERY_LEN: usize = 2000;
+
+/// Placeholder replacing the API key in `debug_params`
+const REDACTED_API_KEY: &str = "<redacted>";
+
+/// Partial response selector keeping everything but the `searchInfo` snippets
+const FIELDS_WITHOUT_SNIPPETS: &str =
+    "kind,totalItems,items(kind,id,etag,selfLink,volumeInfo,saleInfo,accessInfo)";
+
+/// Google Books website, used for human-facing links
+const GOOGLE_BOOKS_WEB_URL: &str = "https://books.google.com/";
 
 #[derive(Debug, Clone)]
 pub enum Projection {
@@ -57,6 +79,69 @@ impl std::fmt::Display for PrintType {
     }
 }
 
+/// Ordering of search results.
+#[derive(Debug, Clone, PartialEq)]
+pub enum OrderBy {
+    /// Returns results in order of relevance (default).
+    Relevance,
+    /// Returns results in order of most recently published
+    Newest,
+}
+
+impl std::fmt::Display for OrderBy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OrderBy::Relevance => write!(f, "relevance"),
+            OrderBy::Newest => write!(f, "newest"),
+        }
+    }
+}
+
+/// Availability filter of the results.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Filter {
+    /// Returns results where at least part of the text is previewable.
+    Partial,
+    /// Returns results where all of the text is viewable.
+    Full,
+    /// Returns free Google eBooks.
+    FreeEbooks,
+    /// Returns Google eBooks with a price.
+    PaidEbooks,
+    /// Returns Google eBooks, paid or free.
+    Ebooks,
+}
+
+impl std::fmt::Display for Filter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Filter::Partial => write!(f, "partial"),
+            Filter::Full => write!(f, "full"),
+            Filter::FreeEbooks => write!(f, "free-ebooks"),
+            Filter::PaidEbooks => write!(f, "paid-ebooks"),
+            Filter::Ebooks => write!(f, "ebooks"),
+        }
+    }
+}
+
+/// Maximum maturity rating of the returned content.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MaturityRating {
+    /// Returns all content, including mature content.
+    Mature,
+    /// Returns only non-mature content.
+    NotMature,
+}
+
+impl std::fmt::Display for MaturityRating {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MaturityRating::Mature => write!(f, "mature"),
+            MaturityRating::NotMature => write!(f, "not-mature"),
+        }
+    }
+}
+
 /// Query builder for searching volumes in the Google Books API.
 ///
 /// Uses the Builder pattern to construct queries in a fluent manner.
@@ -89,6 +174,20 @@ pub struct VolumeQuery {
     pub projection: Option<Projection>,
     /// Print type to filter results.
     pub print_type: Option<PrintType>,
+    /// Ordering of the results.
+    pub order_by: Option<OrderBy>,
+    /// Maximum maturity rating of the results.
+    pub max_allowed_maturity_rating: Option<MaturityRating>,
+    /// Availability filter of the results.
+    pub filter: Option<Filter>,
+    /// Partial response selector (e.g. "items(id,volumeInfo/title)").
+    pub fields: Option<String>,
+    /// Partner source token.
+    pub source: Option<String>,
+    /// ISO 3166-1 country code used for sale availability (e.g. "FR").
+    pub country: Option<String>,
+    /// Requests partner preview links (`gbpv=1`).
+    pub preview_mode: bool,
 }
 
 impl VolumeQuery {
@@ -112,77 +211,268 @@ impl VolumeQuery {
             lang_restrict: None,
             projection: None,
             print_type: None,
+            order_by: None,
+            max_allowed_maturity_rating: None,
+            filter: None,
+            fields: None,
+            source: None,
+            country: None,
+            preview_mode: false,
         }
     }
 
+    /// Builds a query from what a user typed in a search box:
+    ///
+    /// * an ISBN-like input (10 or 13 digits, with optional hyphens or
+    ///   spaces and an `X` check digit) becomes an `isbn` query
+    /// * a `title:`, `author:`, `publisher:`, `subject:` or `isbn:` prefix
+    ///   (case-insensitive) is routed to the matching constructor
+    /// * anything else is kept as free text, including Google's own
+    ///   qualifiers such as `intitle:`
+    pub fn heuristic(input: &str) -> Self {
+        let input = input.trim();
+
+        if is_isbn_like(input) {
+            return Self::isbn(isbn::normalize(input));
+        }
+
+        if let Some((prefix, value)) = input.split_once(':') {
+            let value = value.trim().to_string();
+            match prefix.trim().to_lowercase().as_str() {
+                "title" => return Self::title(value),
+                "author" => return Self::author(value),
+                "publisher" => return Self::publisher(value),
+                "subject" => return Self::subject(value),
+                "isbn" => return Self::isbn(isbn::normalize(&value)),
+                _ => {}
+            }
+        }
+
+        Self::new(input)
+    }
+
     /// Creates a search query by ISBN.
     pub fn isbn(isbn: impl Into<String>) -> Self {
-        Self::new(format!("isbn:{}", isbn.into()))
+        Self::new(qualified("isbn", isbn.into()))
     }
 
     /// Creates a search query by title.
     pub fn title(title: impl Into<String>) -> Self {
-        Self::new(format!("intitle:{}", title.into()))
+        Self::new(qualified("intitle", title.into()))
+    }
+
+    /// Creates a search query by title that also matches the title as free
+    /// text, so near-misses of a strict `intitle:` still match.
+    pub fn title_fuzzy(title: impl Into<String>) -> Self {
+        let title = title.into();
+
+        Self::title(title.clone()).and_term(title)
     }
 
     /// Creates a search query by author.
     pub fn author(author: impl Into<String>) -> Self {
-        Self::new(format!("inauthor:{}", author.into()))
+        Self::new(qualified("inauthor", author.into()))
     }
 
     /// Creates a search query by publisher.
     pub fn publisher(publisher: impl Into<String>) -> Self {
-        Self::new(format!("inpublisher:{}", publisher.into()))
+        Self::new(qualified("inpublisher", publisher.into()))
     }
 
     /// Creates a search query by subjext.
     pub fn subject(subject: impl Into<String>) -> Self {
-        Self::new(format!("subject:{}", subject.into()))
+        Self::new(qualified("subject", subject.into()))
     }
 
     /// Creates a search query by lccn.
     pub fn lccn(lccn: impl Into<String>) -> Self {
-        Self::new(format!("lccn:{}", lccn.into()))
+        Self::new(qualified("lccn", lccn.into()))
     }
 
     /// Creates a search query by oclc.
     pub fn oclc(oclc: impl Into<String>) -> Self {
-        Self::new(format!("oclc:{}", oclc.into()))
+        Self::new(qualified("oclc", oclc.into()))
     }
 
-    pub fn and_isbn(mut self, isbn: impl Into<String>) -> Self {
-        self.q.push_str(&format!(" isbn:{}", isbn.into()));
-        self
+    /// Same as `isbn`, failing on an empty or whitespace-only value.
+    pub fn try_isbn(isbn: impl Into<String>) -> Result<Self, QueryError> {
+        try_qualified("isbn", isbn.into()).map(Self::new)
     }
 
-    pub fn and_title(mut self, title: impl Into<String>) -> Self {
-        self.q.push_str(&format!(" intitle:{}", title.into()));
-        self
+    /// Same as `title`, failing on an empty or whitespace-only value.
+    pub fn try_title(title: impl Into<String>) -> Result<Self, QueryError> {
+        try_qualified("intitle", title.into()).map(Self::new)
     }
 
-    pub fn and_author(mut self, author: impl Into<String>) -> Self {
-        self.q.push_str(&format!(" inauthor:{}", author.into()));
-        self
+    /// Same as `author`, failing on an empty or whitespace-only value.
+    pub fn try_author(author: impl Into<String>) -> Result<Self, QueryError> {
+        try_qualified("inauthor", author.into()).map(Self::new)
     }
 
-    pub fn and_publisher(mut self, publisher: impl Into<String>) -> Self {
-        self.q
-            .push_str(&format!(" inpublisher:{}", publisher.into()));
-        self
+    /// Same as `publisher`, failing on an empty or whitespace-only value.
+    pub fn try_publisher(publisher: impl Into<String>) -> Result<Self, QueryError> {
+        try_qualified("inpublisher", publisher.into()).map(Self::new)
     }
 
-    pub fn and_subject(mut self, subject: impl Into<String>) -> Self {
-        self.q.push_str(&format!(" subject:{}", subject.into()));
-        self
+    /// Same as `subject`, failing on an empty or whitespace-only value.
+    pub fn try_subject(subject: impl Into<String>) -> Result<Self, QueryError> {
+        try_qualified("subject", subject.into()).map(Self::new)
     }
 
-    pub fn and_lccn(mut self, lccn: impl Into<String>) -> Self {
-        self.q.push_str(&format!(" lccn:{}", lccn.into()));
-        self
+    /// Same as `lccn`, failing on an empty or whitespace-only value.
+    pub fn try_lccn(lccn: impl Into<String>) -> Result<Self, QueryError> {
+        try_qualified("lccn", lccn.into()).map(Self::new)
+    }
+
+    /// Same as `oclc`, failing on an empty or whitespace-only value.
+    pub fn try_oclc(oclc: impl Into<String>) -> Result<Self, QueryError> {
+        try_qualified("oclc", oclc.into()).map(Self::new)
+    }
+
+    /// Creates a search query by title restricted to large-print editions.
+    pub fn large_print(title: impl Into<String>) -> Self {
+        Self::title(title).and_term("\"large print\"".to_string())
+    }
+
+    /// Creates a search query matching a series or collection name as an exact phrase.
+    pub fn series(name: impl Into<String>) -> Self {
+        Self::new(exact_phrase(&name.into()))
+    }
+
+    /// Creates a search query requiring every word to appear in the title.
+    pub fn title_all_words(words: &[&str]) -> Self {
+        Self::new(join_terms(
+            words
+                .iter()
+                .map(|word| qualified("intitle", word.to_string())),
+            " ",
+        ))
+    }
+
+    /// Creates a search query matching any of the given ISBNs (e.g. the
+    /// ISBN-10 and ISBN-13 of a work). ISBNs are normalized first.
+    pub fn isbn_any(isbns: &[&str]) -> Self {
+        Self::new(join_terms(
+            isbns
+                .iter()
+                .map(|value| qualified("isbn", isbn::normalize(value))),
+            " OR ",
+        ))
+    }
+
+    /// Creates a search query matching books in every given subject.
+    /// Multi-word subjects are quoted so the qualifier applies to the whole
+    /// subject, and empty subjects are skipped.
+    pub fn all_subjects(subjects: &[&str]) -> Self {
+        Self::new(join_terms(
+            subjects
+                .iter()
+                .map(|subject| qualified("subject", grouped(subject))),
+            " ",
+        ))
+    }
+
+    /// Creates a search query matching books by any of the given authors.
+    /// Multi-word names are quoted so the qualifier applies to the whole
+    /// name, and empty names are skipped.
+    pub fn any_author(authors: &[&str]) -> Self {
+        Self::new(join_terms(
+            authors
+                .iter()
+                .map(|author| qualified("inauthor", grouped(author))),
+            " OR ",
+        ))
+    }
+
+    /// Creates a search query matching an exact phrase in the full text of
+    /// the books (e.g. `intext:"mutual aid"`).
+    pub fn full_text_phrase(phrase: impl Into<String>) -> Self {
+        Self::new(qualified("intext", exact_phrase(&phrase.into())))
+    }
+
+    /// Creates a search query with the year as a free-text term.
+    ///
+    /// Google has no strict publication date filter, so this is best-effort:
+    /// books merely mentioning the year may match, and some books published
+    /// that year may be missed.
+    pub fn published_in(year: i32) -> Self {
+        Self::new(year.to_string())
+    }
+
+    /// Creates a search query by title with the year as a free term, to
+    /// disambiguate editions.
+    pub fn title_with_year(title: impl Into<String>, year: i32) -> Self {
+        Self::title(title).and_term(year.to_string())
+    }
+
+    /// Creates a search query matching the exact title, restricted to a language.
+    pub fn exact_title_in_lang(title: impl Into<String>, lang: impl Into<String>) -> Self {
+        Self::new(qualified("intitle", exact_phrase(&title.into()))).lang_restrict(lang.into())
+    }
+
+    /// Creates a search query matching the exact publisher name, restricted
+    /// to a language.
+    pub fn publisher_in_lang(publisher: impl Into<String>, lang: impl Into<String>) -> Self {
+        Self::new(qualified("inpublisher", exact_phrase(&publisher.into())))
+            .lang_restrict(lang.into())
+    }
+
+    /// Creates a search query requiring both the title and the author to match.
+    pub fn title_and_author(title: impl Into<String>, author: impl Into<String>) -> Self {
+        Self::title(title).and_author(author)
+    }
+
+    /// Creates a search query restricted to a publisher within a subject.
+    pub fn publisher_and_subject(publisher: impl Into<String>, subject: impl Into<String>) -> Self {
+        Self::publisher(publisher).and_subject(subject)
+    }
+
+    pub fn and_isbn(self, isbn: impl Into<String>) -> Self {
+        self.and_qualified("isbn", isbn.into())
+    }
+
+    pub fn and_title(self, title: impl Into<String>) -> Self {
+        self.and_qualified("intitle", title.into())
+    }
+
+    pub fn and_author(self, author: impl Into<String>) -> Self {
+        self.and_qualified("inauthor", author.into())
+    }
+
+    pub fn and_publisher(self, publisher: impl Into<String>) -> Self {
+        self.and_qualified("inpublisher", publisher.into())
+    }
+
+    pub fn and_subject(self, subject: impl Into<String>) -> Self {
+        self.and_qualified("subject", subject.into())
+    }
+
+    pub fn and_lccn(self, lccn: impl Into<String>) -> Self {
+        self.and_qualified("lccn", lccn.into())
+    }
+
+    pub fn and_oclc(self, oclc: impl Into<String>) -> Self {
+        self.and_qualified("oclc", oclc.into())
+    }
+
+    /// Adds the year as a free-text term, see `published_in`.
+    pub fn and_published_in(self, year: i32) -> Self {
+        self.and_term(year.to_string())
     }
 
-    pub fn and_oclc(mut self, oclc: impl Into<String>) -> Self {
-        self.q.push_str(&format!(" oclc:{}", oclc.into()));
+    /// Appends a `qualifier:value` term, unless the value is empty.
+    fn and_qualified(self, qualifier: &str, value: String) -> Self {
+        self.and_term(qualified(qualifier, value))
+    }
+
+    /// Appends a term to `q`, unless it is empty.
+    fn and_term(mut self, term: String) -> Self {
+        if !term.is_empty() {
+            if !self.q.is_empty() {
+                self.q.push(' ');
+            }
+            self.q.push_str(&term);
+        }
         self
     }
 
@@ -191,6 +481,23 @@ impl VolumeQuery {
         self
     }
 
+    /// Page size the API will use for this query: `max_results` when set,
+    /// otherwise the API default of 10.
+    pub fn effective_max_results(&self) -> i32 {
+        self.max_results.unwrap_or(DEFAULT_MAX_RESULTS)
+    }
+
+    /// Returns the query of the previous page: `start_index` moved back by
+    /// the effective page size, clamped at zero.
+    pub fn prev_page(&self) -> VolumeQuery {
+        let start_index = self
+            .start_index
+            .unwrap_or(0)
+            .saturating_sub(self.effective_max_results());
+
+        self.clone().start_index(start_index.max(0))
+    }
+
     pub fn start_index(mut self, index: i32) -> Self {
         self.start_index = Some(index);
         self
@@ -211,6 +518,116 @@ impl VolumeQuery {
         self
     }
 
+    pub fn order_by(mut self, order_by: OrderBy) -> Self {
+        self.order_by = Some(order_by);
+        self
+    }
+
+    /// Orders results by relevance, overriding any previously set ordering.
+    pub fn most_relevant(self) -> Self {
+        self.order_by(OrderBy::Relevance)
+    }
+
+    pub fn max_allowed_maturity_rating(mut self, rating: MaturityRating) -> Self {
+        self.max_allowed_maturity_rating = Some(rating);
+        self
+    }
+
+    pub fn filter(mut self, filter: Filter) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
+    /// Only returns the selected response fields, see Google's partial
+    /// response syntax.
+    ///
+    /// Replaces any previous selector, including `without_snippets`.
+    pub fn fields(mut self, fields: impl Into<String>) -> Self {
+        self.fields = Some(fields.into());
+        self
+    }
+
+    /// Drops the `searchInfo` text snippets from the results to reduce the
+    /// payload size.
+    ///
+    /// This sets the `fields` selector, so it replaces a previous `fields`
+    /// call and is replaced by a later one (last one wins).
+    pub fn without_snippets(self) -> Self {
+        self.fields(FIELDS_WITHOUT_SNIPPETS)
+    }
+
+    /// Identifies the Google Books partner issuing the request with the
+    /// `source` token it was given.
+    pub fn source(mut self, source: impl Into<String>) -> Self {
+        self.source = Some(source.into());
+        self
+    }
+
+    /// Sends the `gbpv=1` flag partners use to get preview-enabled links,
+    /// usually along with `source`. Nothing is sent when disabled.
+    pub fn preview_mode(mut self, enabled: bool) -> Self {
+        self.preview_mode = enabled;
+        self
+    }
+
+    /// Sets the country used for sale availability.
+    pub fn country(mut self, country: impl Into<String>) -> Self {
+        self.country = Some(country.into());
+        self
+    }
+
+    /// Restricts the results to ebooks for sale in the given country, by
+    /// setting both the `PaidEbooks` filter and the country.
+    pub fn buyable_in(self, country: impl Into<String>) -> Self {
+        self.filter(Filter::PaidEbooks).country(country)
+    }
+
+    /// Includes mature content in the results.
+    ///
+    /// This disables Google's SafeSearch-style filtering, so results may
+    /// contain adult content.
+    pub fn include_mature(self) -> Self {
+        self.max_allowed_maturity_rating(MaturityRating::Mature)
+    }
+
+    /// Returns the free-text terms of `q`, leaving out `qualifier:value`
+    /// terms and `OR` operators, so snippets can be highlighted client-side.
+    /// Quoted phrases are returned whole, without their quotes.
+    pub fn matched_terms(&self) -> Vec<String> {
+        split_terms(&self.q)
+            .into_iter()
+            .filter(|term| term != "OR" && !is_qualified(term))
+            .map(|term| term.trim_matches('"').to_string())
+            .filter(|term| !term.is_empty())
+            .collect()
+    }
+
+    /// Checks the query before it is sent, failing with
+    /// `QueryError::QueryTooLong` when `q` is longer than `MAX_QUERY_LEN`
+    /// characters.
+    pub fn validate(&self) -> Result<(), QueryError> {
+        let len = self.q.chars().count();
+        ensure!(
+            len <= MAX_QUERY_LEN,
+            QueryTooLongSnafu {
+                len,
+                max: MAX_QUERY_LEN
+            }
+        );
+
+        Ok(())
+    }
+
+    /// Builds a link to this search on the Google Books website.
+    ///
+    /// Unlike `build_url`, the result is meant to be opened by a human and
+    /// only carries the search string.
+    pub fn web_search_url(&self) -> String {
+        reqwest::Url::parse_with_params(GOOGLE_BOOKS_WEB_URL, [("q", &self.q)])
+            .unwrap()
+            .to_string()
+    }
+
     /// Builds the final query URL.
     ///
     /// # Arguments
@@ -226,6 +643,18 @@ impl VolumeQuery {
     /// This method is typically called internally by the client and
     pub fn build_url(&self, base: &str, api_key: Option<String>) -> reqwest::Url {
         let base_url = &format!("{}/books/v1/volumes", base);
+
+        reqwest::Url::parse_with_params(base_url, self.params(api_key)).unwrap()
+    }
+
+    /// Returns the query parameters `build_url` would send, with the API key
+    /// redacted, to check which filters are actually applied.
+    pub fn debug_params(&self, api_key: Option<&str>) -> Vec<(String, String)> {
+        self.params(api_key.map(|_| REDACTED_API_KEY.to_string()))
+    }
+
+    /// Query parameters of the request, in the order they are sent.
+    fn params(&self, api_key: Option<String>) -> Vec<(String, String)> {
         let mut queries: Vec<(String, String)> = Vec::with_capacity(5);
 
         queries.push(("q".to_string(), self.q.clone()));
@@ -236,9 +665,6 @@ impl VolumeQuery {
         if let Some(start_index) = self.start_index {
             queries.push(("startIndex".to_string(), start_index.to_string()));
         }
-        if let Some(start_index) = self.start_index {
-            queries.push(("startIndex".to_string(), start_index.to_string()));
-        }
         if let Some(lang) = self.lang_restrict.clone() {
             queries.push(("langRestrict".to_string(), lang));
         }
@@ -246,16 +672,130 @@ impl VolumeQuery {
             queries.push(("projection".to_string(), projection.to_string()));
         }
         if let Some(print_type) = self.print_type.clone() {
-            queries.push(("projection".to_string(), print_type.to_string()));
+            queries.push(("printType".to_string(), print_type.to_string()));
+        }
+        if let Some(order_by) = self.order_by.clone() {
+            queries.push(("orderBy".to_string(), order_by.to_string()));
+        }
+        if let Some(rating) = self.max_allowed_maturity_rating.clone() {
+            queries.push(("maxAllowedMaturityRating".to_string(), rating.to_string()));
+        }
+        if let Some(filter) = self.filter.clone() {
+            queries.push(("filter".to_string(), filter.to_string()));
+        }
+        if let Some(fields) = self.fields.clone() {
+            queries.push(("fields".to_string(), fields));
+        }
+        if let Some(country) = self.country.clone() {
+            queries.push(("country".to_string(), country));
+        }
+        if let Some(source) = self.source.clone() {
+            queries.push(("source".to_string(), source));
+        }
+        if self.preview_mode {
+            queries.push(("gbpv".to_string(), "1".to_string()));
         }
         if let Some(key) = api_key {
             queries.push(("key".to_string(), key.to_string()));
         }
 
-        reqwest::Url::parse_with_params(base_url, queries).unwrap()
+        queries
+    }
+}
+
+/// Builds a `qualifier:value` term, or an empty string when the value is
+/// empty or whitespace-only (Google treats a bare `intitle:` oddly).
+fn qualified(qualifier: &str, value: String) -> String {
+    if value.trim().is_empty() {
+        String::new()
+    } else {
+        format!("{}:{}", qualifier, value)
+    }
+}
+
+/// Quotes a value as an exact phrase, or returns an empty string when the
+/// value is empty or whitespace-only.
+fn exact_phrase(value: &str) -> String {
+    let value = value.trim();
+    if value.is_empty() {
+        String::new()
+    } else {
+        format!("\"{}\"", value)
+    }
+}
+
+/// Quotes multi-word values so a qualifier applies to all their words.
+fn grouped(value: &str) -> String {
+    let value = value.trim();
+    if value.contains(char::is_whitespace) {
+        exact_phrase(value)
+    } else {
+        value.to_string()
     }
 }
 
+/// Joins the non-empty terms with `separator`.
+fn join_terms(terms: impl Iterator<Item = String>, separator: &str) -> String {
+    terms
+        .filter(|term| !term.is_empty())
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+/// Builds a `qualifier:value` term, failing when the value is empty.
+fn try_qualified(qualifier: &str, value: String) -> Result<String, QueryError> {
+    ensure!(!value.trim().is_empty(), EmptyQualifierSnafu { qualifier });
+
+    Ok(format!("{}:{}", qualifier, value))
+}
+
+/// Returns true for inputs made of 10 or 13 ISBN characters, ignoring
+/// hyphens and spaces.
+fn is_isbn_like(input: &str) -> bool {
+    let valid_chars = input
+        .chars()
+        .all(|c| c.is_ascii_digit() || c == '-' || c == ' ' || c.eq_ignore_ascii_case(&'x'));
+    let normalized = isbn::normalize(input);
+
+    valid_chars
+        && matches!(normalized.len(), 10 | 13)
+        && normalized[..normalized.len() - 1]
+            .chars()
+            .all(|c| c.is_ascii_digit())
+}
+
+/// Splits a query string on whitespace, keeping quoted phrases together.
+fn split_terms(q: &str) -> Vec<String> {
+    let mut terms = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+
+    for c in q.chars() {
+        if c == '"' {
+            quoted = !quoted;
+            current.push(c);
+        } else if c.is_whitespace() && !quoted {
+            if !current.is_empty() {
+                terms.push(std::mem::take(&mut current));
+            }
+        } else {
+            current.push(c);
+        }
+    }
+    if !current.is_empty() {
+        terms.push(current);
+    }
+
+    terms
+}
+
+/// Returns true for `qualifier:value` terms (e.g. `intitle:dune`).
+fn is_qualified(term: &str) -> bool {
+    term.split_once(':').is_some_and(|(prefix, _)| {
+        !prefix.is_empty() && prefix.chars().all(|c| c.is_ascii_alphabetic())
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -330,6 +870,66 @@ mod tests {
         assert!(url.as_str().contains("key=api_key"));
     }
 
+    #[test]
+    fn test_without_snippets() {
+        let params = VolumeQuery::new("rust")
+            .without_snippets()
+            .debug_params(None);
+        let fields = params
+            .iter()
+            .find(|(name, _)| name == "fields")
+            .map(|(_, value)| value.as_str())
+            .unwrap();
+
+        assert!(fields.contains("volumeInfo"));
+        assert!(!fields.contains("searchInfo"));
+
+        let query = VolumeQuery::new("rust")
+            .without_snippets()
+            .fields("items(id)");
+        assert_eq!(query.fields.as_deref(), Some("items(id)"));
+    }
+
+    #[test]
+    fn test_buyable_in() {
+        let params = VolumeQuery::title("Dune")
+            .buyable_in("FR")
+            .debug_params(None);
+
+        assert!(params.contains(&("filter".to_string(), "paid-ebooks".to_string())));
+        assert!(params.contains(&("country".to_string(), "FR".to_string())));
+    }
+
+    #[test]
+    fn test_preview_mode() {
+        let has_gbpv = |query: VolumeQuery| {
+            query
+                .debug_params(None)
+                .contains(&("gbpv".to_string(), "1".to_string()))
+        };
+
+        assert!(has_gbpv(
+            VolumeQuery::new("rust")
+                .source("partner")
+                .preview_mode(true)
+        ));
+        assert!(!has_gbpv(VolumeQuery::new("rust").preview_mode(false)));
+        assert!(!has_gbpv(VolumeQuery::new("rust")));
+    }
+
+    #[test]
+    fn test_validate_query_length() {
+        assert!(VolumeQuery::title("Dune").validate().is_ok());
+
+        let query = (0..300).fold(VolumeQuery::new("rust"), |query, i| {
+            query.and_subject(format!("subject{}", i))
+        });
+        assert!(matches!(
+            query.validate(),
+            Err(QueryError::QueryTooLong { max: 2000, .. })
+        ));
+    }
+
     #[test]
     fn test_lccn_query() {
         let query = VolumeQuery::lccn("Yolo");
@@ -342,10 +942,316 @@ mod tests {
         assert_eq!(Projection::Lite.to_string(), "lite");
     }
 
+    #[test]
+    fn test_filter_display() {
+        assert_eq!(Filter::Partial.to_string(), "partial");
+        assert_eq!(Filter::Full.to_string(), "full");
+        assert_eq!(Filter::FreeEbooks.to_string(), "free-ebooks");
+        assert_eq!(Filter::PaidEbooks.to_string(), "paid-ebooks");
+        assert_eq!(Filter::Ebooks.to_string(), "ebooks");
+    }
+
     #[test]
     fn test_print_type_display() {
         assert_eq!(PrintType::Books.to_string(), "books");
         assert_eq!(PrintType::All.to_string(), "all");
         assert_eq!(PrintType::Magazines.to_string(), "magazines");
     }
+
+    #[test]
+    fn test_large_print_query() {
+        let query = VolumeQuery::large_print("Les Misérables");
+        assert!(query.q.contains("intitle:Les Misérables"));
+        assert!(query.q.contains("\"large print\""));
+    }
+
+    #[test]
+    fn test_most_relevant_overrides_newest() {
+        let query = VolumeQuery::author("Ursula K. Le Guin")
+            .order_by(OrderBy::Newest)
+            .most_relevant();
+
+        assert_eq!(query.order_by, Some(OrderBy::Relevance));
+        assert!(query
+            .build_url("https://www.googleapis.com", None)
+            .as_str()
+            .contains("orderBy=relevance"));
+    }
+
+    #[test]
+    fn test_web_search_url() {
+        let url = VolumeQuery::title("mutual aid").web_search_url();
+        let parsed = reqwest::Url::parse(&url).unwrap();
+
+        assert_eq!(parsed.host_str(), Some("books.google.com"));
+        assert!(url.contains("q=intitle%3Amutual+aid"));
+    }
+
+    #[test]
+    fn test_series_query() {
+        let query = VolumeQuery::series("Les Rougon-Macquart");
+        assert_eq!(query.q, "\"Les Rougon-Macquart\"");
+    }
+
+    #[test]
+    fn test_title_all_words_query() {
+        let query = VolumeQuery::title_all_words(&["conquest", "bread"]);
+        assert_eq!(query.q, "intitle:conquest intitle:bread");
+
+        let query = VolumeQuery::title_all_words(&[]);
+        assert_eq!(query.q, "");
+    }
+
+    #[test]
+    fn test_isbn_any_query() {
+        let query = VolumeQuery::isbn_any(&["2-348-05469-3", "978-2-348-05469-3"]);
+        assert_eq!(query.q, "isbn:2348054693 OR isbn:9782348054693");
+
+        let query = VolumeQuery::isbn_any(&[]);
+        assert_eq!(query.q, "");
+    }
+
+    #[test]
+    fn test_heuristic_isbn() {
+        assert_eq!(
+            VolumeQuery::heuristic(" 978-2-348-05469-3 ").q,
+            "isbn:9782348054693"
+        );
+        assert_eq!(VolumeQuery::heuristic("019853453x").q, "isbn:019853453X");
+    }
+
+    #[test]
+    fn test_heuristic_prefix() {
+        assert_eq!(VolumeQuery::heuristic("title: Dune").q, "intitle:Dune");
+        assert_eq!(
+            VolumeQuery::heuristic("Author:Frank Herbert").q,
+            "inauthor:Frank Herbert"
+        );
+    }
+
+    #[test]
+    fn test_heuristic_free_text() {
+        assert_eq!(VolumeQuery::heuristic("dune messiah").q, "dune messiah");
+        assert_eq!(VolumeQuery::heuristic("1984").q, "1984");
+        assert_eq!(VolumeQuery::heuristic("intitle:dune").q, "intitle:dune");
+    }
+
+    #[test]
+    fn test_full_text_phrase_query() {
+        let query = VolumeQuery::full_text_phrase("mutual aid");
+        assert_eq!(query.q, "intext:\"mutual aid\"");
+    }
+
+    #[test]
+    fn test_published_in_query() {
+        assert_eq!(VolumeQuery::published_in(1984).q, "1984");
+        assert_eq!(
+            VolumeQuery::author("Ursula K. Le Guin")
+                .and_published_in(1974)
+                .q,
+            "inauthor:Ursula K. Le Guin 1974"
+        );
+    }
+
+    #[test]
+    fn test_title_fuzzy_query() {
+        let query = VolumeQuery::title_fuzzy("Neuromancer");
+        assert_eq!(query.q, "intitle:Neuromancer Neuromancer");
+    }
+
+    #[test]
+    fn test_all_subjects_query() {
+        let query = VolumeQuery::all_subjects(&["History", "Science"]);
+        assert_eq!(query.q, "subject:History subject:Science");
+
+        let query = VolumeQuery::all_subjects(&[]);
+        assert_eq!(query.q, "");
+    }
+
+    #[test]
+    fn test_all_subjects_skips_empty_subjects() {
+        let query = VolumeQuery::all_subjects(&["", "history", " "]);
+        assert_eq!(query.q, "subject:history");
+
+        let query = VolumeQuery::all_subjects(&["Science Fiction", "Space"]);
+        assert_eq!(query.q, "subject:\"Science Fiction\" subject:Space");
+    }
+
+    #[test]
+    fn test_any_author_query() {
+        let query = VolumeQuery::any_author(&["Ursula K. Le Guin", "Octavia E. Butler"]);
+        assert_eq!(
+            query.q,
+            "inauthor:\"Ursula K. Le Guin\" OR inauthor:\"Octavia E. Butler\""
+        );
+
+        let query = VolumeQuery::any_author(&["Tolkien"]);
+        assert_eq!(query.q, "inauthor:Tolkien");
+
+        let query = VolumeQuery::any_author(&[]);
+        assert_eq!(query.q, "");
+    }
+
+    #[test]
+    fn test_any_author_skips_empty_names() {
+        let query = VolumeQuery::any_author(&["", "Tolkien", "  "]);
+        assert_eq!(query.q, "inauthor:Tolkien");
+    }
+
+    #[test]
+    fn test_prev_page() {
+        let query = VolumeQuery::new("rust").max_results(20).start_index(50);
+        assert_eq!(query.prev_page().start_index, Some(30));
+    }
+
+    #[test]
+    fn test_prev_page_clamps_at_zero() {
+        let query = VolumeQuery::new("rust").start_index(5);
+        assert_eq!(query.prev_page().start_index, Some(0));
+        assert_eq!(VolumeQuery::new("rust").prev_page().start_index, Some(0));
+    }
+
+    #[test]
+    fn test_prev_page_saturates() {
+        let query = VolumeQuery::new("rust").start_index(i32::MIN);
+        assert_eq!(query.prev_page().start_index, Some(0));
+    }
+
+    #[test]
+    fn test_effective_max_results() {
+        assert_eq!(VolumeQuery::new("rust").effective_max_results(), 10);
+        assert_eq!(
+            VolumeQuery::new("rust")
+                .max_results(25)
+                .effective_max_results(),
+            25
+        );
+    }
+
+    #[test]
+    fn test_title_with_year_query() {
+        let query = VolumeQuery::title_with_year("Nineteen Eighty-Four", 1949);
+        assert!(query.q.contains("intitle:Nineteen Eighty-Four"));
+        assert!(query.q.ends_with(" 1949"));
+    }
+
+    #[test]
+    fn test_exact_title_in_lang_query() {
+        let query = VolumeQuery::exact_title_in_lang("L'Étranger", "fr");
+        assert_eq!(query.q, "intitle:\"L'Étranger\"");
+        assert_eq!(query.lang_restrict, Some("fr".to_string()));
+    }
+
+    #[test]
+    fn test_include_mature() {
+        let query = VolumeQuery::new("research").include_mature();
+        let url = query.build_url("https://www.googleapis.com", None);
+
+        assert_eq!(
+            query.max_allowed_maturity_rating,
+            Some(MaturityRating::Mature)
+        );
+        assert!(url.as_str().contains("maxAllowedMaturityRating=mature"));
+    }
+
+    #[test]
+    fn test_matched_terms() {
+        let query = VolumeQuery::new("anarchism \"mutual aid\"")
+            .and_author("Kropotkin")
+            .and_title("\"large print\"");
+        assert_eq!(query.matched_terms(), vec!["anarchism", "mutual aid"]);
+
+        let query = VolumeQuery::new("isbn:123 OR history");
+        assert_eq!(query.matched_terms(), vec!["history"]);
+    }
+
+    #[test]
+    fn test_debug_params() {
+        let query = VolumeQuery::author("Victor Hugo")
+            .max_results(20)
+            .start_index(40)
+            .lang_restrict("fr".to_string())
+            .projection(Projection::Lite)
+            .print_type(PrintType::Books);
+
+        let params = query.debug_params(Some("secret"));
+        let expected: Vec<(String, String)> = [
+            ("q", "inauthor:Victor Hugo"),
+            ("maxResults", "20"),
+            ("startIndex", "40"),
+            ("langRestrict", "fr"),
+            ("projection", "lite"),
+            ("printType", "books"),
+            ("key", "<redacted>"),
+        ]
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect();
+
+        assert_eq!(params, expected);
+    }
+
+    #[test]
+    fn test_title_and_author_query() {
+        let query = VolumeQuery::title_and_author("Dune", "Frank Herbert");
+        assert_eq!(query.q, "intitle:Dune inauthor:Frank Herbert");
+    }
+
+    #[test]
+    fn test_publisher_in_lang_query() {
+        let query = VolumeQuery::publisher_in_lang("Éditions de Minuit", "fr");
+        assert_eq!(query.q, "inpublisher:\"Éditions de Minuit\"");
+        assert_eq!(query.lang_restrict.as_deref(), Some("fr"));
+    }
+
+    #[test]
+    fn test_publisher_and_subject_query() {
+        let query = VolumeQuery::publisher_and_subject("Tor", "Fantasy");
+        assert!(query.q.contains("inpublisher:Tor"));
+        assert!(query.q.contains("subject:Fantasy"));
+    }
+
+    #[test]
+    fn test_empty_qualifiers_are_skipped() {
+        assert_eq!(VolumeQuery::title("").q, "");
+        assert_eq!(VolumeQuery::author("   ").q, "");
+        assert_eq!(
+            VolumeQuery::title("Dune")
+                .and_author(" ")
+                .and_publisher("")
+                .q,
+            "intitle:Dune"
+        );
+        assert_eq!(
+            VolumeQuery::title("").and_author("Frank Herbert").q,
+            "inauthor:Frank Herbert"
+        );
+    }
+
+    #[test]
+    fn test_empty_values_are_skipped_by_composite_builders() {
+        assert_eq!(
+            VolumeQuery::title_all_words(&["conquest", " ", "bread"]).q,
+            "intitle:conquest intitle:bread"
+        );
+        assert_eq!(
+            VolumeQuery::isbn_any(&["", "2-348-05469-3"]).q,
+            "isbn:2348054693"
+        );
+        assert_eq!(VolumeQuery::large_print("").q, "\"large print\"");
+        assert_eq!(VolumeQuery::series("  ").q, "");
+        assert_eq!(VolumeQuery::full_text_phrase("").q, "");
+        assert_eq!(VolumeQuery::title_with_year("", 1949).q, "1949");
+        assert_eq!(VolumeQuery::exact_title_in_lang(" ", "fr").q, "");
+        assert_eq!(VolumeQuery::publisher_in_lang("", "fr").q, "");
+    }
+
+    #[test]
+    fn test_try_qualifiers() {
+        assert_eq!(VolumeQuery::try_title("Dune").unwrap().q, "intitle:Dune");
+        assert!(matches!(
+            VolumeQuery::try_author("  "),
+            Err(QueryError::EmptyQualifier { qualifier }) if qualifier == "inauthor"
+        ));
+    }
 }